@@ -0,0 +1,46 @@
+use crate::ExprValue;
+use std::rc::Rc;
+
+/// A declared enum, through which its variants are reached as properties.
+#[derive(Debug)]
+pub struct Enum {
+    pub name: String,
+    /// Each variant's name and the names of its payload's fields, in declaration order.
+    pub variants: Vec<(String, Vec<String>)>,
+}
+
+impl Enum {
+    pub fn variant(&self, name: &str) -> Option<usize> {
+        self.variants
+            .iter()
+            .position(|(variant, _)| variant == name)
+    }
+}
+
+/// A value of an enum. Variants are equal when they're the same variant of the same
+/// declaration, carrying equal payloads.
+#[derive(Debug)]
+pub struct Variant {
+    pub owner: Rc<Enum>,
+    pub index: usize,
+    /// One value per field, empty for a variant without a payload.
+    pub payload: Vec<ExprValue>,
+}
+
+impl Variant {
+    pub fn name(&self) -> &str {
+        &self.owner.variants[self.index].0
+    }
+
+    pub fn fields(&self) -> &[String] {
+        &self.owner.variants[self.index].1
+    }
+
+    /// The payload value of the field `name`.
+    pub fn field(&self, name: &str) -> Option<ExprValue> {
+        self.fields()
+            .iter()
+            .position(|field| field == name)
+            .map(|position| self.payload[position].clone())
+    }
+}
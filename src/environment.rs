@@ -1,9 +1,15 @@
 use crate::{ExprValue, RuntimeError, Token};
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
 
-#[derive(Default)]
+#[derive(Debug, Default)]
 pub struct Environment {
     values: HashMap<String, ExprValue>,
+    // names bound with `const`, which can't be reassigned
+    constants: HashSet<String>,
     enclosing: Option<Rc<RefCell<Environment>>>,
 }
 
@@ -11,6 +17,7 @@ impl Environment {
     pub fn new() -> Self {
         Environment {
             values: HashMap::new(),
+            constants: HashSet::new(),
             enclosing: None,
         }
     }
@@ -18,40 +25,119 @@ impl Environment {
     pub fn with_enclosing(enclosing: Rc<RefCell<Environment>>) -> Self {
         Environment {
             values: HashMap::new(),
+            constants: HashSet::new(),
             enclosing: Some(enclosing),
         }
     }
 
     pub fn define(&mut self, name: String, value: ExprValue) {
+        self.constants.remove(&name);
         self.values.insert(name, value);
     }
 
+    pub fn define_const(&mut self, name: String, value: ExprValue) {
+        self.constants.insert(name.clone());
+        self.values.insert(name, value);
+    }
+
+    /// Whether `name` is a constant declared in this scope (enclosing scopes aren't checked).
+    pub fn is_const(&self, name: &str) -> bool {
+        self.constants.contains(name)
+    }
+
+    /// Looks up a binding in this scope only, without walking enclosing scopes.
+    pub fn get_own(&self, name: &str) -> Option<ExprValue> {
+        self.values.get(name).cloned()
+    }
+
     pub fn get(&self, name: &Token) -> Result<ExprValue, RuntimeError> {
         if let Some(val) = self.values.get(&name.lexeme) {
             Ok(val.clone())
         } else if let Some(enclosing) = &self.enclosing {
             enclosing.borrow().get(name)
         } else {
-            Err(RuntimeError {
-                token: name.lexeme.clone(),
-                message: format!("Undefined variable '{}'.", name.lexeme),
-                line: name.line,
-            })
+            Err(RuntimeError::new(
+                &name.lexeme,
+                format!("Undefined variable '{}'.", name.lexeme),
+                name.line,
+            ))
         }
     }
 
     pub fn assign(&mut self, name: &Token, value: ExprValue) -> Result<(), RuntimeError> {
-        if self.values.contains_key(&name.lexeme) {
+        if self.constants.contains(&name.lexeme) {
+            Err(self.const_assign_error(name))
+        } else if self.values.contains_key(&name.lexeme) {
             self.values.insert(name.lexeme.clone(), value);
             Ok(())
         } else if let Some(enclosing) = &mut self.enclosing {
             enclosing.borrow_mut().assign(name, value)
         } else {
-            Err(RuntimeError {
-                token: name.lexeme.clone(),
-                message: format!("Undefined variable '{}'", name.lexeme),
-                line: name.line,
-            })
+            Err(self.undefined_assign_error(name))
+        }
+    }
+
+    /// Fails the way `assign` would, without assigning anything.
+    pub fn check_assign(&self, name: &Token) -> Result<(), RuntimeError> {
+        if self.constants.contains(&name.lexeme) {
+            Err(self.const_assign_error(name))
+        } else if self.values.contains_key(&name.lexeme) {
+            Ok(())
+        } else if let Some(enclosing) = &self.enclosing {
+            enclosing.borrow().check_assign(name)
+        } else {
+            Err(self.undefined_assign_error(name))
+        }
+    }
+
+    /// Replaces a variable's value with `update(old)` in the scope that defines it,
+    /// returning both the old and the new value. No scope is borrowed while `update`
+    /// runs, so it's free to read and assign variables itself.
+    pub fn update<F>(
+        environment: &Rc<RefCell<Environment>>,
+        name: &Token,
+        update: F,
+    ) -> Result<(ExprValue, ExprValue), RuntimeError>
+    where
+        F: FnOnce(&ExprValue) -> Result<ExprValue, RuntimeError>,
+    {
+        let mut scope = environment.clone();
+        loop {
+            let enclosing = {
+                let current = scope.borrow();
+                if current.constants.contains(&name.lexeme) {
+                    return Err(current.const_assign_error(name));
+                } else if current.values.contains_key(&name.lexeme) {
+                    break;
+                }
+                current.enclosing.clone()
+            };
+
+            scope = enclosing.ok_or_else(|| scope.borrow().undefined_assign_error(name))?;
         }
+
+        let old_val = scope.borrow().values[&name.lexeme].clone();
+        let new_val = update(&old_val)?;
+        scope
+            .borrow_mut()
+            .values
+            .insert(name.lexeme.clone(), new_val.clone());
+        Ok((old_val, new_val))
+    }
+
+    fn undefined_assign_error(&self, name: &Token) -> RuntimeError {
+        RuntimeError::new(
+            &name.lexeme,
+            format!("Undefined variable '{}'", name.lexeme),
+            name.line,
+        )
+    }
+
+    fn const_assign_error(&self, name: &Token) -> RuntimeError {
+        RuntimeError::new(
+            &name.lexeme,
+            format!("Cannot assign to constant '{}'.", name.lexeme),
+            name.line,
+        )
     }
 }
@@ -1,12 +1,22 @@
-use crate::Token;
-use std::fmt::{Display, Formatter};
+use crate::format::float_literal;
+use crate::{Pattern, Stmt, Token, TypeAnnotation};
+use std::{
+    fmt::{Display, Formatter},
+    rc::Rc,
+};
 
 #[derive(Debug, Clone)]
 pub enum Expr {
+    Int(i64),
     Number(f64),
     String(String),
     Bool(bool),
     Nil,
+    List(Vec<Expr>),
+    Map {
+        brace: Token,
+        entries: Vec<(Expr, Expr)>,
+    },
     Grouping(Box<Expr>),
     Unary {
         operator: Token,
@@ -17,26 +27,245 @@ pub enum Expr {
         left: Box<Expr>,
         right: Box<Expr>,
     },
+    Logical {
+        operator: Token,
+        left: Box<Expr>,
+        right: Box<Expr>,
+    },
+    /// `value is Type`, naming the type rather than evaluating it.
+    Is {
+        value: Box<Expr>,
+        keyword: Token,
+        type_name: Token,
+    },
+    /// `start..end` or `start..=end`, told apart by the operator.
+    Range {
+        start: Box<Expr>,
+        operator: Token,
+        end: Box<Expr>,
+    },
+    Conditional {
+        condition: Box<Expr>,
+        then_branch: Box<Expr>,
+        else_branch: Box<Expr>,
+    },
+    Call {
+        callee: Box<Expr>,
+        paren: Token,
+        arguments: Vec<Expr>,
+        optional: bool,
+    },
+    Get {
+        object: Box<Expr>,
+        name: Token,
+        optional: bool,
+    },
+    Index {
+        object: Box<Expr>,
+        bracket: Token,
+        index: Box<Expr>,
+    },
+    Slice {
+        object: Box<Expr>,
+        bracket: Token,
+        start: Option<Box<Expr>>,
+        end: Option<Box<Expr>>,
+    },
+    SetIndex {
+        object: Box<Expr>,
+        bracket: Token,
+        index: Box<Expr>,
+        value: Box<Expr>,
+    },
     Variable(Token),
     Assign(Token, Box<Expr>),
+    /// `[a, b] = value`, assigning to existing variables.
+    AssignPattern {
+        pattern: Box<Pattern>,
+        equals: Token,
+        value: Box<Expr>,
+    },
+    CompoundAssign {
+        target: Box<Expr>,
+        operator: Token,
+        value: Box<Expr>,
+    },
+    Increment {
+        target: Box<Expr>,
+        operator: Token,
+        postfix: bool,
+    },
+    /// `fun (a, b) { ... }`, or `(a, b) => expr` whose body is a single `return`.
+    /// The body is shared with every function value created from it.
+    Function {
+        keyword: Token,
+        /// Set when the function comes from a `fun name() { }` declaration.
+        name: Option<Token>,
+        params: Vec<Param>,
+        return_type: Option<TypeAnnotation>,
+        body: Rc<Vec<Stmt>>,
+        /// Whether the body has a `yield`, so calling it returns a generator.
+        generator: bool,
+    },
+}
+
+/// A function parameter: `name`, `name = default` or the rest parameter `...name`.
+#[derive(Debug, Clone)]
+pub struct Param {
+    pub name: Token,
+    /// Evaluated at call time, in the call's scope, when the argument is left out.
+    pub default: Option<Expr>,
+    /// Collects the remaining arguments into a list; only the last parameter can be one.
+    pub rest: bool,
+    pub annotation: Option<TypeAnnotation>,
 }
 
 impl Display for Expr {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            Expr::Number(num) => write!(f, "{num:?}"),
+            // integer literals print like the float ones so `parse` output is unchanged
+            Expr::Int(num) => write!(f, "{num}.0"),
+            Expr::Number(num) => write!(f, "{}", float_literal(*num)),
             Expr::String(string) => write!(f, "{string}"),
             Expr::Bool(boolean) => write!(f, "{boolean}"),
             Expr::Nil => write!(f, "nil"),
+            Expr::List(elements) => {
+                write!(f, "(list")?;
+                for element in elements {
+                    write!(f, " {element}")?;
+                }
+                write!(f, ")")
+            }
             Expr::Unary { operator, right } => write!(f, "({} {right})", operator.lexeme),
             Expr::Binary {
                 operator,
                 right,
                 left,
             } => write!(f, "({} {left} {right})", operator.lexeme),
+            Expr::Map { entries, .. } => {
+                write!(f, "(map")?;
+                for (key, value) in entries {
+                    write!(f, " ({key} {value})")?;
+                }
+                write!(f, ")")
+            }
             Expr::Grouping(expr) => write!(f, "(group {expr})"),
+            Expr::Logical {
+                operator,
+                left,
+                right,
+            } => write!(f, "({} {left} {right})", operator.lexeme),
+            Expr::Call {
+                callee,
+                arguments,
+                optional,
+                ..
+            } => {
+                let call = if *optional { "?.call" } else { "call" };
+                write!(f, "({call} {callee}")?;
+                for argument in arguments {
+                    write!(f, " {argument}")?;
+                }
+                write!(f, ")")
+            }
+            Expr::Get {
+                object,
+                name,
+                optional,
+            } => {
+                let dot = if *optional { "?." } else { "." };
+                write!(f, "({dot} {object} {})", name.lexeme)
+            }
+            Expr::Conditional {
+                condition,
+                then_branch,
+                else_branch,
+            } => write!(f, "(?: {condition} {then_branch} {else_branch})"),
+            Expr::Is {
+                value, type_name, ..
+            } => write!(f, "(is {value} {})", type_name.lexeme),
+            Expr::Range {
+                start,
+                operator,
+                end,
+            } => write!(f, "({} {start} {end})", operator.lexeme),
+            Expr::Index { object, index, .. } => write!(f, "(index {object} {index})"),
+            Expr::Slice {
+                object, start, end, ..
+            } => {
+                write!(f, "(slice {object}")?;
+                for bound in [start, end] {
+                    match bound {
+                        Some(bound) => write!(f, " {bound}")?,
+                        None => write!(f, " nil")?,
+                    }
+                }
+                write!(f, ")")
+            }
+            Expr::SetIndex {
+                object,
+                index,
+                value,
+                ..
+            } => write!(f, "(index {object} {index}) = {value}"),
             Expr::Variable(var) => write!(f, "{}", var.lexeme),
             Expr::Assign(tok, expr) => write!(f, "{} = {}", tok.lexeme, expr),
+            Expr::AssignPattern { pattern, value, .. } => write!(f, "{pattern} = {value}"),
+            Expr::CompoundAssign {
+                target,
+                operator,
+                value,
+            } => write!(f, "({} {target} {value})", operator.lexeme),
+            Expr::Increment {
+                target,
+                operator,
+                postfix,
+            } => match postfix {
+                true => write!(f, "({target} {})", operator.lexeme),
+                false => write!(f, "({} {target})", operator.lexeme),
+            },
+            Expr::Function {
+                name,
+                params,
+                return_type,
+                body,
+                ..
+            } => {
+                let params = params
+                    .iter()
+                    .map(|param| param.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" ");
+
+                match name {
+                    Some(name) => write!(f, "(fun {} ({params})", name.lexeme)?,
+                    None => write!(f, "(fun ({params})")?,
+                }
+                if let Some(return_type) = return_type {
+                    write!(f, ": {return_type}")?;
+                }
+                for stmt in body.iter() {
+                    write!(f, " {stmt}")?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+impl Display for Param {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.rest {
+            write!(f, "...")?;
+        }
+        write!(f, "{}", self.name.lexeme)?;
+
+        if let Some(annotation) = &self.annotation {
+            write!(f, ": {annotation}")?;
+        }
+        match &self.default {
+            Some(default) => write!(f, "={default}"),
+            None => Ok(()),
         }
     }
 }
@@ -0,0 +1,124 @@
+use crate::ExprValue;
+
+/// How a value is rendered: `Str` is what `print` shows, `Repr` is the debugging form,
+/// which quotes strings and keeps the decimal point on integral floats so that `"1"`,
+/// `1` and `1.0` can be told apart. Elements of lists and maps are always shown as `Repr`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Style {
+    Str,
+    Repr,
+}
+
+/// Formats a float with at least one digit after the decimal point, as the scanner
+/// shows number literals. Infinities and NaN are left as they are.
+pub fn float_literal(n: f64) -> String {
+    let text = n.to_string();
+
+    if !n.is_finite() || text.contains('.') {
+        text
+    } else {
+        format!("{text}.0")
+    }
+}
+
+/// Formats `value` in the given style. This is what `Display` and so `print` use.
+/// There's no user-defined `toString` hook until the language has class instances.
+pub fn format_value(value: &ExprValue, style: Style) -> String {
+    ValueFormatter { open: Vec::new() }.format(value, style)
+}
+
+struct ValueFormatter {
+    // the lists and maps being formatted, to spot one that contains itself
+    open: Vec<*const ()>,
+}
+
+impl ValueFormatter {
+    fn format(&mut self, value: &ExprValue, style: Style) -> String {
+        match value {
+            ExprValue::Number(n) => match style {
+                Style::Str => {
+                    let text = float_literal(*n);
+                    text.strip_suffix(".0").map(str::to_string).unwrap_or(text)
+                }
+                Style::Repr => float_literal(*n),
+            },
+            ExprValue::String(s) => match style {
+                Style::Str => s.clone(),
+                Style::Repr => format!("\"{s}\""),
+            },
+            ExprValue::List(list) => {
+                let id = list.as_ptr() as *const ();
+                if self.open.contains(&id) {
+                    return "[...]".to_string();
+                }
+
+                self.open.push(id);
+                let elements = list
+                    .borrow()
+                    .iter()
+                    .map(|element| self.format(element, Style::Repr))
+                    .collect::<Vec<String>>();
+                self.open.pop();
+
+                format!("[{}]", elements.join(", "))
+            }
+            ExprValue::Map(map) => {
+                let id = map.as_ptr() as *const ();
+                if self.open.contains(&id) {
+                    return "{...}".to_string();
+                }
+
+                self.open.push(id);
+                let entries = map
+                    .borrow()
+                    .iter()
+                    .map(|(key, value)| {
+                        format!(
+                            "{}: {}",
+                            self.format(key, Style::Repr),
+                            self.format(value, Style::Repr)
+                        )
+                    })
+                    .collect::<Vec<String>>();
+                self.open.pop();
+
+                format!("{{{}}}", entries.join(", "))
+            }
+            ExprValue::Bool(b) => b.to_string(),
+            ExprValue::Int(n) => n.to_string(),
+            ExprValue::Range(range) => {
+                let operator = if range.inclusive { "..=" } else { ".." };
+                match range.step {
+                    1 => format!("{}{operator}{}", range.start, range.end),
+                    step => format!("{}{operator}{} step {step}", range.start, range.end),
+                }
+            }
+            ExprValue::NativeFunction(function) => format!("<native fn {}>", function.name),
+            ExprValue::Function(function) => match &function.name {
+                Some(name) => format!("<fn {name}>"),
+                None => "<fn>".to_string(),
+            },
+            ExprValue::Generator(generator) => match &generator.borrow().name {
+                Some(name) => format!("<generator {name}>"),
+                None => "<generator>".to_string(),
+            },
+            ExprValue::Error(err) => err.message.clone(),
+            ExprValue::Module(module) => format!("<module {}>", module.name),
+            ExprValue::Enum(enumeration) => format!("<enum {}>", enumeration.name),
+            ExprValue::Variant(variant) => {
+                let name = format!("{}.{}", variant.owner.name, variant.name());
+                if variant.payload.is_empty() {
+                    return name;
+                }
+
+                let payload = variant
+                    .payload
+                    .iter()
+                    .map(|value| self.format(value, Style::Repr))
+                    .collect::<Vec<String>>();
+                format!("{name}({})", payload.join(", "))
+            }
+            ExprValue::Nil => "nil".to_string(),
+        }
+    }
+}
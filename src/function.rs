@@ -0,0 +1,32 @@
+use crate::{Environment, Param, Stmt};
+use std::{cell::RefCell, rc::Rc};
+
+/// A function written in Lox. It closes over the scope it was created in, so it can
+/// still see (and update) that scope's variables after the scope has been left.
+#[derive(Debug)]
+pub struct Function {
+    /// `None` for anonymous functions.
+    pub name: Option<String>,
+    pub params: Vec<Param>,
+    pub body: Rc<Vec<Stmt>>,
+    /// Calling a generator function returns a generator over its body instead of running it.
+    pub generator: bool,
+    pub closure: Rc<RefCell<Environment>>,
+}
+
+impl Function {
+    /// The fewest and most arguments a call may pass; there's no maximum when the
+    /// function has a rest parameter.
+    pub fn arity(&self) -> (usize, Option<usize>) {
+        let required = self
+            .params
+            .iter()
+            .filter(|param| param.default.is_none() && !param.rest)
+            .count();
+
+        match self.params.last() {
+            Some(param) if param.rest => (required, None),
+            _ => (required, Some(self.params.len())),
+        }
+    }
+}
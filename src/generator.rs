@@ -0,0 +1,357 @@
+use crate::iteration::targets_loop;
+use crate::{
+    Environment, Expr, ExprValue, Interpreter, Iteration, RuntimeError, Stmt, Token, Unwind,
+};
+use std::{cell::RefCell, rc::Rc};
+
+/// A suspended call to a generator function.
+///
+/// The body runs on an explicit stack of frames instead of the Rust stack, so it can
+/// stop at a `yield` and carry on from there at the next `resume`. Only the statements
+/// that can hold a `yield` (blocks, loops, `try` and `match`) get frames; everything
+/// else is handed to the interpreter whole.
+#[derive(Debug)]
+pub struct Generator {
+    pub name: Option<String>,
+    interpreter: Interpreter,
+    // empty once the body has finished
+    frames: Vec<Frame>,
+}
+
+#[derive(Debug)]
+enum Frame {
+    Block {
+        statements: Rc<Vec<Stmt>>,
+        next: usize,
+        environment: Rc<RefCell<Environment>>,
+    },
+    /// A `for-in` loop; each iteration's body runs as a block above it.
+    Loop {
+        keyword: Token,
+        label: Option<String>,
+        variable: String,
+        iteration: Iteration,
+        body: Rc<Vec<Stmt>>,
+        environment: Rc<RefCell<Environment>>,
+    },
+    /// A `while` loop; each iteration's body runs as a block above it.
+    While {
+        label: Option<String>,
+        condition: Expr,
+        body: Rc<Vec<Stmt>>,
+        environment: Rc<RefCell<Environment>>,
+    },
+    /// A `try` statement whose body or handler is the block above it.
+    Try {
+        catch: Option<(Token, Rc<Vec<Stmt>>)>,
+        finally: Option<Rc<Vec<Stmt>>>,
+        stage: TryStage,
+        // what the `finally` block interrupted, resumed once it completes
+        pending: Option<Unwind>,
+        environment: Rc<RefCell<Environment>>,
+    },
+}
+
+#[derive(Debug, PartialEq)]
+enum TryStage {
+    Body,
+    Catch,
+    Finally,
+}
+
+impl Generator {
+    /// `interpreter` is set up over the call's scope, `environment`, with the
+    /// arguments already bound.
+    pub(crate) fn new(
+        name: Option<String>,
+        interpreter: Interpreter,
+        environment: Rc<RefCell<Environment>>,
+        body: Rc<Vec<Stmt>>,
+    ) -> Self {
+        Generator {
+            name,
+            interpreter,
+            frames: vec![Frame::Block {
+                statements: body,
+                next: 0,
+                environment,
+            }],
+        }
+    }
+
+    /// Runs the body up to its next `yield`, returning the yielded value, or `None` once
+    /// the body has finished. A generator that raised an error is finished.
+    pub fn resume(&mut self) -> Result<Option<ExprValue>, RuntimeError> {
+        // every other kind of frame always has a block running above it
+        while let Some(Frame::Block {
+            statements,
+            next,
+            environment,
+        }) = self.frames.last_mut()
+        {
+            if *next == statements.len() {
+                self.frames.pop();
+                self.block_finished()?;
+                continue;
+            }
+
+            let (statements, environment) = (statements.clone(), environment.clone());
+            let stmt = &statements[*next];
+            *next += 1;
+
+            self.interpreter.set_env(environment.clone());
+            match self.step(stmt, environment) {
+                Ok(Some(value)) => return Ok(Some(value)),
+                Ok(None) => (),
+                Err(unwind) => self.unwind(unwind)?,
+            }
+        }
+
+        Ok(None)
+    }
+
+    // runs one statement, returning the value it yields if it's a `yield`
+    fn step(
+        &mut self,
+        stmt: &Stmt,
+        environment: Rc<RefCell<Environment>>,
+    ) -> Result<Option<ExprValue>, Unwind> {
+        match stmt {
+            Stmt::Yield(_, value) => match value {
+                Some(value) => Ok(Some(self.interpreter.evaluate(value)?)),
+                None => Ok(Some(ExprValue::Nil)),
+            },
+            Stmt::Block(statements) => {
+                self.push_block(statements.clone(), Environment::with_enclosing(environment));
+                Ok(None)
+            }
+            Stmt::ForIn {
+                keyword,
+                label,
+                variable,
+                iterable,
+                body,
+            } => {
+                let iterable = self.interpreter.evaluate(iterable)?;
+                let iteration = self.interpreter.iterate(iterable, keyword)?;
+
+                self.frames.push(Frame::Loop {
+                    keyword: keyword.clone(),
+                    label: label.as_ref().map(|label| label.lexeme.clone()),
+                    variable: variable.lexeme.clone(),
+                    iteration,
+                    body: body.clone(),
+                    environment,
+                });
+                self.next_iteration()?;
+                Ok(None)
+            }
+            Stmt::While {
+                label,
+                condition,
+                body,
+            } => {
+                self.frames.push(Frame::While {
+                    label: label.as_ref().map(|label| label.lexeme.clone()),
+                    condition: condition.clone(),
+                    body: body.clone(),
+                    environment,
+                });
+                self.next_iteration()?;
+                Ok(None)
+            }
+            Stmt::Try {
+                body,
+                catch,
+                finally,
+            } => {
+                self.frames.push(Frame::Try {
+                    catch: catch.clone(),
+                    finally: finally.clone(),
+                    stage: TryStage::Body,
+                    pending: None,
+                    environment: environment.clone(),
+                });
+                self.push_block(body.clone(), Environment::with_enclosing(environment));
+                Ok(None)
+            }
+            Stmt::Match {
+                keyword,
+                value,
+                value_line,
+                arms,
+            } => {
+                let value = self.interpreter.evaluate(value)?;
+                let (arm, scope) =
+                    self.interpreter
+                        .select_arm(keyword, *value_line, arms, &value)?;
+
+                self.frames.push(Frame::Block {
+                    statements: arm.body.clone(),
+                    next: 0,
+                    environment: scope,
+                });
+                Ok(None)
+            }
+            stmt => {
+                self.interpreter.execute(stmt)?;
+                Ok(None)
+            }
+        }
+    }
+
+    fn push_block(&mut self, statements: Rc<Vec<Stmt>>, scope: Environment) {
+        self.frames.push(Frame::Block {
+            statements,
+            next: 0,
+            environment: Rc::new(RefCell::new(scope)),
+        });
+    }
+
+    // starts the next iteration of the loop on top of the stack, or leaves the loop
+    fn next_iteration(&mut self) -> Result<(), Unwind> {
+        let (body, scope) = match self.frames.last_mut() {
+            Some(Frame::Loop {
+                keyword,
+                variable,
+                iteration,
+                body,
+                environment,
+                ..
+            }) => match self.interpreter.next_value(iteration, keyword)? {
+                Some(value) => {
+                    let mut scope = Environment::with_enclosing(environment.clone());
+                    scope.define(variable.clone(), value);
+                    (body.clone(), scope)
+                }
+                None => {
+                    self.frames.pop();
+                    return Ok(());
+                }
+            },
+            Some(Frame::While {
+                condition,
+                body,
+                environment,
+                ..
+            }) => {
+                // the condition is evaluated in the scope around the loop
+                self.interpreter.set_env(environment.clone());
+                let condition = self.interpreter.evaluate(condition)?;
+                if !self.interpreter.is_truthy(&condition) {
+                    self.frames.pop();
+                    return Ok(());
+                }
+                let scope = Environment::with_enclosing(environment.clone());
+                (body.clone(), scope)
+            }
+            _ => unreachable!("only loops have iterations!"),
+        };
+
+        self.push_block(body, scope);
+        Ok(())
+    }
+
+    // moves on from a block that ran to its end
+    fn block_finished(&mut self) -> Result<(), RuntimeError> {
+        match self.frames.last_mut() {
+            None | Some(Frame::Block { .. }) => Ok(()),
+            Some(Frame::Loop { .. } | Frame::While { .. }) => match self.next_iteration() {
+                Ok(()) => Ok(()),
+                Err(unwind) => self.unwind(unwind),
+            },
+            Some(Frame::Try {
+                finally,
+                stage,
+                pending,
+                environment,
+                ..
+            }) => {
+                if *stage != TryStage::Finally {
+                    if let Some(finally) = finally.clone() {
+                        *stage = TryStage::Finally;
+                        let scope = Environment::with_enclosing(environment.clone());
+                        self.push_block(finally, scope);
+                        return Ok(());
+                    }
+                }
+
+                // the `finally` block (if any) is done: carry on with what it interrupted
+                let pending = pending.take();
+                self.frames.pop();
+
+                match pending {
+                    Some(unwind) => self.unwind(unwind),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+
+    /// Pops frames until one handles `unwind`, as the interpreter's own loops and `try`
+    /// statements would. An error nothing catches finishes the generator and is returned.
+    fn unwind(&mut self, unwind: Unwind) -> Result<(), RuntimeError> {
+        while let Some(frame) = self.frames.last_mut() {
+            match frame {
+                Frame::Block { .. } => {
+                    self.frames.pop();
+                }
+                Frame::Loop { label, .. } | Frame::While { label, .. } => match &unwind {
+                    Unwind::Break(target) if targets_loop(target, label) => {
+                        self.frames.pop();
+                        return Ok(());
+                    }
+                    Unwind::Continue(target) if targets_loop(target, label) => {
+                        return match self.next_iteration() {
+                            Ok(()) => Ok(()),
+                            Err(unwind) => self.unwind(unwind),
+                        };
+                    }
+                    _ => {
+                        self.frames.pop();
+                    }
+                },
+                Frame::Try {
+                    catch,
+                    finally,
+                    stage,
+                    pending,
+                    environment,
+                } => {
+                    let environment = environment.clone();
+
+                    if let (TryStage::Body, Some((name, handler)), Unwind::Error(err)) =
+                        (&stage, catch.clone(), &unwind)
+                    {
+                        *stage = TryStage::Catch;
+
+                        let mut scope = Environment::with_enclosing(environment);
+                        scope.define(name.lexeme.clone(), err.clone().into_value());
+                        self.push_block(handler, scope);
+                        return Ok(());
+                    }
+
+                    if *stage != TryStage::Finally {
+                        if let Some(finally) = finally.clone() {
+                            *stage = TryStage::Finally;
+                            *pending = Some(unwind);
+                            self.push_block(finally, Environment::with_enclosing(environment));
+                            return Ok(());
+                        }
+                    }
+
+                    // leaving a `finally` block early drops whatever it interrupted
+                    self.frames.pop();
+                }
+            }
+        }
+
+        match unwind {
+            Unwind::Error(err) => Err(err),
+            Unwind::Return(_) => Ok(()),
+            Unwind::Break(_) | Unwind::Continue(_) => {
+                unreachable!("loop control should not escape a function!")
+            }
+        }
+    }
+}
@@ -1,42 +1,168 @@
-use crate::Environment;
-use crate::{Expr, Stmt, Token, TokenType};
+use crate::format::{self, Style};
+use crate::iteration::targets_loop;
+use crate::native;
+use crate::{
+    Enum, Environment, Function, Generator, Module, ModuleCache, ModuleState, Parser, Scanner,
+    Variant,
+};
+use crate::{
+    Expr, Map, MapKey, MatchArm, NativeError, NativeFunction, Pattern, Range, Stmt, Token,
+    TokenType, Type,
+};
 use std::{
-    cell::RefCell,
+    cell::{Cell, RefCell},
+    cmp::Ordering,
     fmt::{Display, Formatter},
+    fs,
+    path::PathBuf,
     rc::Rc,
 };
 
-#[derive(Debug, thiserror::Error)]
+#[derive(Debug, Clone, thiserror::Error)]
 #[error("[line {line}] Error with `{token}`: {message}")]
 pub struct RuntimeError {
     pub token: String,
     pub message: String,
     pub line: usize,
+    /// The value given to `throw`; `None` for errors raised by the interpreter itself.
+    pub value: Option<Box<ExprValue>>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+impl RuntimeError {
+    /// An error raised by the interpreter itself at `token`.
+    pub fn new(token: impl Into<String>, message: impl Into<String>, line: usize) -> Self {
+        RuntimeError {
+            token: token.into(),
+            message: message.into(),
+            line,
+            value: None,
+        }
+    }
+
+    /// The error a `throw` of `value` raises at `token`; its message is the value's text.
+    pub fn thrown(token: impl Into<String>, value: ExprValue, line: usize) -> Self {
+        RuntimeError {
+            token: token.into(),
+            message: value.to_string(),
+            line,
+            value: Some(Box::new(value)),
+        }
+    }
+
+    /// The value a `catch` clause binds: the thrown value, or the error itself.
+    pub fn into_value(self) -> ExprValue {
+        match self.value {
+            Some(value) => *value,
+            None => ExprValue::Error(Rc::new(self)),
+        }
+    }
+}
+
+/// Why a statement stopped executing early: a runtime error, a `break`/`continue`
+/// unwinding to the loop with the given label (the innermost loop when `None`), or a
+/// `return` carrying its value out of the enclosing function.
+#[derive(Debug)]
+pub enum Unwind {
+    Error(RuntimeError),
+    Break(Option<String>),
+    Continue(Option<String>),
+    Return(ExprValue),
+}
+
+impl From<RuntimeError> for Unwind {
+    fn from(err: RuntimeError) -> Self {
+        Unwind::Error(err)
+    }
+}
+
+#[derive(Debug, Clone)]
 pub enum ExprValue {
     Bool(bool),
+    Int(i64),
     Number(f64),
     String(String),
+    /// Shared and mutable: copies of a list value alias the same elements.
+    List(Rc<RefCell<Vec<ExprValue>>>),
+    Map(Rc<RefCell<Map>>),
+    Range(Range),
+    NativeFunction(Rc<NativeFunction>),
+    Function(Rc<Function>),
+    /// A running call to a generator function; shared, so copies advance together.
+    Generator(Rc<RefCell<Generator>>),
+    /// A caught runtime error, exposing its `message` and `line`.
+    Error(Rc<RuntimeError>),
+    Module(Rc<Module>),
+    /// A declared enum, and a value of one.
+    Enum(Rc<Enum>),
+    Variant(Rc<Variant>),
     Nil,
 }
 
+impl ExprValue {
+    /// The value's runtime type, which is never `Any`, nor `Number` for an Int.
+    pub fn value_type(&self) -> Type {
+        match self {
+            ExprValue::Bool(_) => Type::Bool,
+            ExprValue::Int(_) => Type::Int,
+            ExprValue::Number(_) => Type::Number,
+            ExprValue::String(_) => Type::String,
+            ExprValue::List(_) => Type::List,
+            ExprValue::Map(_) => Type::Map,
+            ExprValue::Range(_) => Type::Range,
+            ExprValue::NativeFunction(_) | ExprValue::Function(_) => Type::Function,
+            ExprValue::Generator(_) => Type::Generator,
+            ExprValue::Error(_) => Type::Error,
+            ExprValue::Module(_) => Type::Module,
+            ExprValue::Enum(_) => Type::Enum,
+            ExprValue::Variant(_) => Type::Variant,
+            ExprValue::Nil => Type::Nil,
+        }
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        self.value_type().name()
+    }
+}
+
 impl Display for ExprValue {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        match self {
-            ExprValue::Bool(b) => write!(f, "{b}"),
-            ExprValue::Number(n) => {
-                // todo: handle '.0' decimal?
-                write!(f, "{n}")
+        write!(f, "{}", format::format_value(self, Style::Str))
+    }
+}
+
+/// Numeric operands after promotion: a float on either side makes both floats.
+#[derive(Debug, Clone, Copy)]
+enum NumOperands {
+    Int(i64, i64),
+    Float(f64, f64),
+}
+
+impl NumOperands {
+    fn promote(left: &ExprValue, right: &ExprValue) -> Option<Self> {
+        match (left, right) {
+            (ExprValue::Int(left), ExprValue::Int(right)) => Some(NumOperands::Int(*left, *right)),
+            (ExprValue::Int(left), ExprValue::Number(right)) => {
+                Some(NumOperands::Float(*left as f64, *right))
             }
-            ExprValue::String(s) => write!(f, "{s}"),
-            ExprValue::Nil => write!(f, "nil"),
+            (ExprValue::Number(left), ExprValue::Int(right)) => {
+                Some(NumOperands::Float(*left, *right as f64))
+            }
+            (ExprValue::Number(left), ExprValue::Number(right)) => {
+                Some(NumOperands::Float(*left, *right))
+            }
+            _ => None,
+        }
+    }
+
+    fn compare(self) -> Option<Ordering> {
+        match self {
+            NumOperands::Int(left, right) => Some(left.cmp(&right)),
+            NumOperands::Float(left, right) => left.partial_cmp(&right),
         }
     }
 }
 
-#[derive(Debug, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
 enum InterpreterStatus {
     #[default]
     Evaluate,
@@ -54,27 +180,65 @@ impl TryFrom<&str> for InterpreterStatus {
     }
 }
 
-#[derive(Default)]
+// how deep function calls may nest before a runaway recursion is reported
+const MAX_CALL_DEPTH: usize = 1000;
+
+#[derive(Debug, Default)]
 pub struct Interpreter {
-    environment: Rc<RefCell<Environment>>,
-    status: InterpreterStatus,
+    // the current scope, swapped as execution enters blocks and function calls
+    environment: RefCell<Rc<RefCell<Environment>>>,
+    status: Cell<InterpreterStatus>,
+    // the file being run, which imports are resolved against
+    script_path: Option<PathBuf>,
+    modules: ModuleCache,
+    // how many function calls deep execution is, shared with the interpreters that run
+    // modules and generators
+    depth: Rc<Cell<usize>>,
 }
 
 impl Interpreter {
     pub fn new() -> Self {
         Interpreter {
-            environment: Rc::new(RefCell::new(Environment::new())),
-            status: InterpreterStatus::Evaluate,
+            environment: RefCell::new(Rc::new(RefCell::new(Environment::with_enclosing(
+                native::globals(),
+            )))),
+            status: Cell::new(InterpreterStatus::Evaluate),
+            script_path: None,
+            modules: ModuleCache::default(),
+            depth: Rc::default(),
+        }
+    }
+
+    pub fn set_script_path(&mut self, path: &str) {
+        // the script counts as a module still loading, so importing it back is a cycle
+        if let Ok(full_path) = fs::canonicalize(path) {
+            self.modules
+                .borrow_mut()
+                .insert(full_path, ModuleState::Loading);
         }
+
+        self.script_path = Some(PathBuf::from(path));
+    }
+
+    pub fn set_env(&self, environment: Rc<RefCell<Environment>>) {
+        *self.environment.borrow_mut() = environment;
     }
 
-    pub fn set_env(&mut self, environment: Rc<RefCell<Environment>>) {
-        self.environment = environment;
+    fn env(&self) -> Rc<RefCell<Environment>> {
+        self.environment.borrow().clone()
     }
 
-    pub fn interpret(&mut self, statements: Vec<Stmt>) -> Result<(), RuntimeError> {
+    pub fn interpret(&self, statements: Vec<Stmt>) -> Result<(), RuntimeError> {
         for statement in statements.iter() {
-            self.execute(statement)?;
+            match self.execute(statement) {
+                Ok(()) => (),
+                Err(Unwind::Error(err)) => return Err(err),
+                // the parser rejects loop control outside of a loop and `return`
+                // outside of a function
+                Err(Unwind::Break(_) | Unwind::Continue(_) | Unwind::Return(_)) => {
+                    unreachable!("loop control should not escape to the top level!")
+                }
+            }
         }
 
         Ok(())
@@ -82,28 +246,122 @@ impl Interpreter {
 
     pub fn set_status(&mut self, status: &str) -> Result<(), String> {
         let status = InterpreterStatus::try_from(status)?;
-        self.status = status;
+        self.status.set(status);
 
         Ok(())
     }
 
-    fn execute(&mut self, stmt: &Stmt) -> Result<(), RuntimeError> {
+    pub(crate) fn execute(&self, stmt: &Stmt) -> Result<(), Unwind> {
         match stmt {
-            Stmt::Expression(_) => self.eval_expr_stmt(stmt),
-            Stmt::Print(_) => self.eval_print_stmt(stmt),
-            Stmt::Var(name, initializer) => self.eval_var_stmt(name, initializer),
+            Stmt::Expression(_) => Ok(self.eval_expr_stmt(stmt)?),
+            Stmt::Print(_) => Ok(self.eval_print_stmt(stmt)?),
+            Stmt::Var(pattern, _, initializer) => {
+                Ok(self.eval_var_stmt(pattern, initializer, false)?)
+            }
+            Stmt::Const(pattern, _, initializer) => {
+                Ok(self.eval_var_stmt(pattern, initializer, true)?)
+            }
             Stmt::Block(statements) => self.eval_block_stmt(statements),
+            Stmt::Import {
+                keyword,
+                path,
+                alias,
+            } => {
+                let module = self.import_module(keyword, path)?;
+                self.env()
+                    .borrow_mut()
+                    .define(alias.lexeme.clone(), ExprValue::Module(module));
+                Ok(())
+            }
+            Stmt::FromImport {
+                keyword,
+                path,
+                names,
+            } => Ok(self.eval_from_import_stmt(keyword, path, names)?),
+            Stmt::Throw(keyword, value) => {
+                Err(Unwind::Error(self.eval_throw_stmt(keyword, value)?))
+            }
+            Stmt::Try {
+                body,
+                catch,
+                finally,
+            } => self.eval_try_stmt(
+                body,
+                catch.as_ref(),
+                finally.as_ref().map(|finally| finally.as_slice()),
+            ),
+            Stmt::ForIn {
+                keyword,
+                label,
+                variable,
+                iterable,
+                body,
+            } => self.eval_for_in_stmt(keyword, label.as_ref(), variable, iterable, body),
+            Stmt::While {
+                label,
+                condition,
+                body,
+            } => self.eval_while_stmt(label.as_ref(), condition, body),
+            Stmt::Enum { name, variants } => {
+                let enumeration = Enum {
+                    name: name.lexeme.clone(),
+                    variants: variants
+                        .iter()
+                        .map(|variant| {
+                            let fields = variant.fields.iter().map(|field| field.lexeme.clone());
+                            (variant.name.lexeme.clone(), fields.collect())
+                        })
+                        .collect(),
+                };
+
+                self.env()
+                    .borrow_mut()
+                    .define(name.lexeme.clone(), ExprValue::Enum(Rc::new(enumeration)));
+                Ok(())
+            }
+            Stmt::Match {
+                keyword,
+                value,
+                value_line,
+                arms,
+            } => {
+                let value = self.evaluate(value)?;
+                let (arm, scope) = self.select_arm(keyword, *value_line, arms, &value)?;
+
+                let prev_env = self.env();
+                self.set_env(scope);
+                let result = arm.body.iter().try_for_each(|stmt| self.execute(stmt));
+                self.set_env(prev_env);
+
+                result
+            }
+            // generator bodies run on their own frames, which handle `yield` themselves
+            Stmt::Yield(..) => unreachable!("yield should only run inside a generator!"),
+            Stmt::Return(_, value) => {
+                let value = match value {
+                    Some(value) => self.evaluate(value)?,
+                    None => ExprValue::Nil,
+                };
+                Err(Unwind::Return(value))
+            }
+            Stmt::Break(_, label) => Err(Unwind::Break(
+                label.as_ref().map(|label| label.lexeme.clone()),
+            )),
+            Stmt::Continue(_, label) => Err(Unwind::Continue(
+                label.as_ref().map(|label| label.lexeme.clone()),
+            )),
         }
     }
 
-    fn eval_block_stmt(&mut self, statements: &[Stmt]) -> Result<(), RuntimeError> {
-        let prev_env = self.environment.clone();
+    // the enclosing scope is restored however the block exits, including loop control
+    fn eval_block_stmt(&self, statements: &[Stmt]) -> Result<(), Unwind> {
+        let prev_env = self.env();
 
         self.set_env(Rc::new(RefCell::new(Environment::with_enclosing(
             prev_env.clone(),
         ))));
 
-        let block_eval: Result<(), RuntimeError> = (|| {
+        let block_eval: Result<(), Unwind> = (|| {
             for stmt in statements.iter() {
                 self.execute(stmt)?;
             }
@@ -114,11 +372,318 @@ impl Interpreter {
         block_eval
     }
 
+    fn eval_from_import_stmt(
+        &self,
+        keyword: &Token,
+        path: &str,
+        names: &[Token],
+    ) -> Result<(), RuntimeError> {
+        let module = self.import_module(keyword, path)?;
+
+        for name in names {
+            let value = module.environment.borrow().get_own(&name.lexeme);
+            let value = value.ok_or_else(|| {
+                RuntimeError::new(
+                    &name.lexeme,
+                    format!("Module '{}' has no binding '{}'.", module.name, name.lexeme),
+                    name.line,
+                )
+            })?;
+
+            // imported constants stay constant
+            let environment = self.env();
+            let mut environment = environment.borrow_mut();
+            if module.environment.borrow().is_const(&name.lexeme) {
+                environment.define_const(name.lexeme.clone(), value);
+            } else {
+                environment.define(name.lexeme.clone(), value);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Loads the module at `path` (relative to the importing file), running its top
+    /// level the first time it is imported and reusing the result afterwards.
+    fn import_module(&self, keyword: &Token, path: &str) -> Result<Rc<Module>, RuntimeError> {
+        let import_error =
+            |message: String| RuntimeError::new(&keyword.lexeme, message, keyword.line);
+
+        let base = match self.script_path.as_ref().and_then(|script| script.parent()) {
+            Some(dir) => dir.to_path_buf(),
+            None => PathBuf::new(),
+        };
+        let full_path = fs::canonicalize(base.join(path))
+            .map_err(|err| import_error(format!("Could not find module '{path}': {err}.")))?;
+
+        let cached = self.modules.borrow().get(&full_path).cloned();
+        match cached {
+            Some(ModuleState::Loaded(module)) => return Ok(module),
+            Some(ModuleState::Loading) => {
+                return Err(import_error(format!("Circular import of module '{path}'.")))
+            }
+            Some(ModuleState::Failed(err)) => return Err(err),
+            None => (),
+        }
+
+        let source = fs::read_to_string(&full_path)
+            .map_err(|err| import_error(format!("Could not read module '{path}': {err}.")))?;
+        let (tokens, errors) = Scanner::new(source).scan_tokens();
+        if let Some(error) = errors.first() {
+            return Err(import_error(format!("In module '{path}': {error}")));
+        }
+        let statements = Parser::new(tokens)
+            .parse()
+            .map_err(|err| import_error(format!("In module '{path}': {err}")))?;
+
+        self.modules
+            .borrow_mut()
+            .insert(full_path.clone(), ModuleState::Loading);
+
+        // modules run silently, even when the importing script is being evaluated
+        let interpreter = Interpreter {
+            environment: RefCell::new(Rc::new(RefCell::new(Environment::with_enclosing(
+                native::globals(),
+            )))),
+            status: Cell::new(InterpreterStatus::Run),
+            script_path: Some(full_path.clone()),
+            modules: self.modules.clone(),
+            depth: self.depth.clone(),
+        };
+
+        if let Err(err) = interpreter.interpret(statements) {
+            self.modules
+                .borrow_mut()
+                .insert(full_path, ModuleState::Failed(err.clone()));
+            return Err(err);
+        }
+
+        let module = Rc::new(Module {
+            name: full_path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            path: full_path.clone(),
+            environment: interpreter.env(),
+        });
+        self.modules
+            .borrow_mut()
+            .insert(full_path, ModuleState::Loaded(module.clone()));
+
+        Ok(module)
+    }
+
+    /// Builds the error a `throw` raises. Rethrowing a caught error raises it unchanged.
+    fn eval_throw_stmt(&self, keyword: &Token, value: &Expr) -> Result<RuntimeError, RuntimeError> {
+        match self.evaluate(value)? {
+            ExprValue::Error(err) => Ok(RuntimeError::clone(&err)),
+            value => Ok(RuntimeError::thrown(&keyword.lexeme, value, keyword.line)),
+        }
+    }
+
+    fn eval_try_stmt(
+        &self,
+        body: &[Stmt],
+        catch: Option<&(Token, Rc<Vec<Stmt>>)>,
+        finally: Option<&[Stmt]>,
+    ) -> Result<(), Unwind> {
+        let mut result = self.eval_block_stmt(body);
+
+        if let Some((name, handler)) = catch {
+            if let Err(Unwind::Error(err)) = result {
+                let mut scope = Environment::with_enclosing(self.env());
+                scope.define(name.lexeme.clone(), err.into_value());
+
+                let prev_env = self.env();
+                self.set_env(Rc::new(RefCell::new(scope)));
+                result = self.eval_block_stmt(handler);
+                self.set_env(prev_env);
+            }
+        }
+
+        // `finally` always runs; if it exits early itself, that replaces the pending result
+        if let Some(finally) = finally {
+            self.eval_block_stmt(finally)?;
+        }
+
+        result
+    }
+
+    fn eval_for_in_stmt(
+        &self,
+        keyword: &Token,
+        label: Option<&Token>,
+        variable: &Token,
+        iterable: &Expr,
+        body: &[Stmt],
+    ) -> Result<(), Unwind> {
+        let label = label.map(|label| label.lexeme.clone());
+        let iterable = self.evaluate(iterable)?;
+        let mut iteration = self.iterate(iterable, keyword)?;
+
+        while let Some(value) = self.next_value(&mut iteration, keyword)? {
+            // a fresh scope per iteration, so closures capture that iteration's value
+            let mut scope = Environment::with_enclosing(self.env());
+            scope.define(variable.lexeme.clone(), value);
+
+            let prev_env = self.env();
+            self.set_env(Rc::new(RefCell::new(scope)));
+            let result = body.iter().try_for_each(|stmt| self.execute(stmt));
+            self.set_env(prev_env);
+
+            match result {
+                Ok(()) => (),
+                Err(Unwind::Break(target)) if targets_loop(&target, &label) => break,
+                Err(Unwind::Continue(target)) if targets_loop(&target, &label) => continue,
+                Err(unwind) => return Err(unwind),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn eval_while_stmt(
+        &self,
+        label: Option<&Token>,
+        condition: &Expr,
+        body: &[Stmt],
+    ) -> Result<(), Unwind> {
+        let label = label.map(|label| label.lexeme.clone());
+
+        while self.is_truthy(&self.evaluate(condition)?) {
+            match self.eval_block_stmt(body) {
+                Ok(()) => (),
+                Err(Unwind::Break(target)) if targets_loop(&target, &label) => break,
+                Err(Unwind::Continue(target)) if targets_loop(&target, &label) => continue,
+                Err(unwind) => return Err(unwind),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Picks the first arm whose pattern matches `value` and whose guard passes,
+    /// returning it with the scope holding its bindings. Matching nothing is an error.
+    pub(crate) fn select_arm<'a>(
+        &self,
+        keyword: &Token,
+        value_line: usize,
+        arms: &'a [MatchArm],
+        value: &ExprValue,
+    ) -> Result<(&'a MatchArm, Rc<RefCell<Environment>>), RuntimeError> {
+        for arm in arms {
+            let mut bindings = Vec::new();
+            if !self.match_pattern(&arm.pattern, value, &mut bindings)? {
+                continue;
+            }
+
+            let mut scope = Environment::with_enclosing(self.env());
+            for (name, value) in bindings {
+                scope.define(name, value);
+            }
+            let scope = Rc::new(RefCell::new(scope));
+
+            if let Some(guard) = &arm.guard {
+                let prev_env = self.env();
+                self.set_env(scope.clone());
+                let passed = self.evaluate(guard).map(|guard| self.is_truthy(&guard));
+                self.set_env(prev_env);
+
+                if !passed? {
+                    continue;
+                }
+            }
+
+            return Ok((arm, scope));
+        }
+
+        Err(RuntimeError::new(
+            &keyword.lexeme,
+            format!(
+                "No match arm matched value: {}.",
+                format::format_value(value, Style::Repr)
+            ),
+            value_line,
+        ))
+    }
+
+    /// Whether `value` matches `pattern`, adding the names it binds to `bindings`.
+    fn match_pattern(
+        &self,
+        pattern: &Pattern,
+        value: &ExprValue,
+        bindings: &mut Vec<(String, ExprValue)>,
+    ) -> Result<bool, RuntimeError> {
+        match pattern {
+            Pattern::Wildcard => Ok(true),
+            Pattern::Binding(name) => {
+                bindings.push((name.lexeme.clone(), value.clone()));
+                Ok(true)
+            }
+            Pattern::Literal(literal) => Ok(self.is_equal(&self.evaluate(literal)?, value)),
+            Pattern::List { elements, rest, .. } => {
+                let ExprValue::List(list) = value else {
+                    return Ok(false);
+                };
+                let list = list.borrow().clone();
+
+                let fits = match rest {
+                    Some(_) => list.len() >= elements.len(),
+                    None => list.len() == elements.len(),
+                };
+                if !fits {
+                    return Ok(false);
+                }
+
+                for (element, value) in elements.iter().zip(&list) {
+                    if !self.match_pattern(element, value, bindings)? {
+                        return Ok(false);
+                    }
+                }
+
+                if let Some(rest) = rest {
+                    let rest_values = list[elements.len()..].to_vec();
+                    bindings.push((
+                        rest.lexeme.clone(),
+                        ExprValue::List(Rc::new(RefCell::new(rest_values))),
+                    ));
+                }
+                Ok(true)
+            }
+            Pattern::Map { entries, .. } => {
+                let ExprValue::Map(map) = value else {
+                    return Ok(false);
+                };
+
+                for (key, pattern) in entries {
+                    let entry = map.borrow().get(&MapKey::String(key.clone())).cloned();
+
+                    match entry {
+                        Some(entry) if self.match_pattern(pattern, &entry, bindings)? => (),
+                        _ => return Ok(false),
+                    }
+                }
+                Ok(true)
+            }
+            Pattern::Alternatives(alternatives) => {
+                for alternative in alternatives {
+                    // a failed alternative may have bound some names before failing
+                    let bound = bindings.len();
+                    if self.match_pattern(alternative, value, bindings)? {
+                        return Ok(true);
+                    }
+                    bindings.truncate(bound);
+                }
+                Ok(false)
+            }
+        }
+    }
+
     fn eval_expr_stmt(&self, stmt: &Stmt) -> Result<(), RuntimeError> {
         match stmt {
             Stmt::Expression(expr) => {
                 let stmt = self.evaluate(expr)?;
-                if self.status == InterpreterStatus::Evaluate {
+                if self.status.get() == InterpreterStatus::Evaluate {
                     println!("{}", stmt);
                 }
                 Ok(())
@@ -138,21 +703,223 @@ impl Interpreter {
         }
     }
 
-    fn eval_var_stmt(&self, name: &Token, initializer: &Expr) -> Result<(), RuntimeError> {
+    fn eval_var_stmt(
+        &self,
+        pattern: &Pattern,
+        initializer: &Expr,
+        is_const: bool,
+    ) -> Result<(), RuntimeError> {
         let expr = self.evaluate(initializer)?;
-        self.environment
-            .borrow_mut()
-            .define(name.lexeme.clone(), expr);
+
+        // nothing is bound unless the whole value destructures
+        let mut bindings = Vec::new();
+        self.destructure(pattern, expr, &mut bindings)?;
+
+        let environment = self.env();
+        let mut environment = environment.borrow_mut();
+        if let Some((name, _)) = bindings
+            .iter()
+            .find(|(name, _)| environment.is_const(&name.lexeme))
+        {
+            return Err(RuntimeError::new(
+                &name.lexeme,
+                format!("Cannot redeclare constant '{}'.", name.lexeme),
+                name.line,
+            ));
+        }
+
+        for (name, value) in bindings {
+            if is_const {
+                environment.define_const(name.lexeme.clone(), value);
+            } else {
+                environment.define(name.lexeme.clone(), value);
+            }
+        }
         Ok(())
     }
 
+    /// Takes `value` apart as `pattern` describes, adding the names it binds to
+    /// `bindings`. Unlike a `match`, a value of the wrong shape is an error.
+    fn destructure<'a>(
+        &self,
+        pattern: &'a Pattern,
+        value: ExprValue,
+        bindings: &mut Vec<(&'a Token, ExprValue)>,
+    ) -> Result<(), RuntimeError> {
+        match pattern {
+            Pattern::Wildcard => Ok(()),
+            Pattern::Binding(name) => {
+                bindings.push((name, value));
+                Ok(())
+            }
+            Pattern::List {
+                bracket,
+                elements,
+                rest,
+            } => {
+                let list = match value {
+                    ExprValue::List(list) => list.borrow().clone(),
+                    other => return Err(self.destructure_error(bracket, "List", &other)),
+                };
+
+                if list.len() < elements.len() {
+                    return Err(RuntimeError::new(
+                        &bracket.lexeme,
+                        format!(
+                            "Missing element at index {} when destructuring a list of length {}.",
+                            list.len(),
+                            list.len()
+                        ),
+                        bracket.line,
+                    ));
+                }
+                if rest.is_none() && list.len() > elements.len() {
+                    return Err(RuntimeError::new(
+                        &bracket.lexeme,
+                        format!(
+                            "Too many elements to destructure: expected {} but got {}.",
+                            elements.len(),
+                            list.len()
+                        ),
+                        bracket.line,
+                    ));
+                }
+
+                let mut values = list.into_iter();
+                for element in elements {
+                    let value = values.next().unwrap_or(ExprValue::Nil);
+                    self.destructure(element, value, bindings)?;
+                }
+
+                if let Some(rest) = rest {
+                    let rest_values = values.collect();
+                    bindings.push((rest, ExprValue::List(Rc::new(RefCell::new(rest_values)))));
+                }
+                Ok(())
+            }
+            Pattern::Map { brace, entries } => {
+                let map = match value {
+                    ExprValue::Map(map) => map,
+                    other => return Err(self.destructure_error(brace, "Map", &other)),
+                };
+
+                for (key, pattern) in entries {
+                    let entry = map.borrow().get(&MapKey::String(key.clone())).cloned();
+                    let entry = entry.ok_or_else(|| {
+                        RuntimeError::new(
+                            &brace.lexeme,
+                            format!("Missing field '{key}' when destructuring a map."),
+                            brace.line,
+                        )
+                    })?;
+
+                    self.destructure(pattern, entry, bindings)?;
+                }
+                Ok(())
+            }
+            // the parser only lets destructurable patterns through
+            Pattern::Literal(_) | Pattern::Alternatives(_) => {
+                unreachable!("only a match can use refutable patterns!")
+            }
+        }
+    }
+
+    fn destructure_error(&self, token: &Token, expected: &str, value: &ExprValue) -> RuntimeError {
+        RuntimeError::new(
+            &token.lexeme,
+            format!(
+                "Expected a {expected} to destructure, got {}.",
+                value.type_name()
+            ),
+            token.line,
+        )
+    }
+
     pub fn evaluate(&self, expr: &Expr) -> Result<ExprValue, RuntimeError> {
         match expr {
             Expr::Bool(b) => Ok(ExprValue::Bool(*b)),
+            Expr::Int(n) => Ok(ExprValue::Int(*n)),
             Expr::Number(n) => Ok(ExprValue::Number(*n)),
             Expr::String(s) => Ok(ExprValue::String(s.to_owned())),
             Expr::Nil => Ok(ExprValue::Nil),
+            Expr::List(elements) => {
+                let elements = elements
+                    .iter()
+                    .map(|element| self.evaluate(element))
+                    .collect::<Result<Vec<ExprValue>, RuntimeError>>()?;
+
+                Ok(ExprValue::List(Rc::new(RefCell::new(elements))))
+            }
+            Expr::Map { brace, entries } => {
+                let mut map = Map::new();
+
+                for (key, value) in entries {
+                    let key = self.evaluate(key)?;
+                    let hashed = self.check_map_key(brace, &key)?;
+                    map.insert(hashed, key, self.evaluate(value)?);
+                }
+
+                Ok(ExprValue::Map(Rc::new(RefCell::new(map))))
+            }
             Expr::Grouping(expr) => self.evaluate(expr),
+            Expr::Is {
+                value, type_name, ..
+            } => {
+                let value = self.evaluate(value)?;
+                Ok(ExprValue::Bool(self.is_type(&value, type_name)?))
+            }
+            Expr::Range {
+                start,
+                operator,
+                end,
+            } => {
+                let bound = |bound: ExprValue| match bound {
+                    ExprValue::Int(n) => Ok(n),
+                    other => Err(RuntimeError::new(
+                        &operator.lexeme,
+                        format!("Range bounds must be integers; got {}.", other.type_name()),
+                        operator.line,
+                    )),
+                };
+                let (start, end) = (bound(self.evaluate(start)?)?, bound(self.evaluate(end)?)?);
+
+                let inclusive = operator.token_type == TokenType::DOTDOTEQUAL;
+                Ok(ExprValue::Range(Range::new(start, end, inclusive)))
+            }
+            Expr::Function {
+                name,
+                params,
+                body,
+                generator,
+                ..
+            } => Ok(ExprValue::Function(Rc::new(Function {
+                name: name.as_ref().map(|name| name.lexeme.clone()),
+                params: params.clone(),
+                body: body.clone(),
+                generator: *generator,
+                closure: self.env(),
+            }))),
+            Expr::Logical {
+                operator,
+                left,
+                right,
+            } => self.evaluate_logical(operator, left, right),
+            Expr::Call { .. } | Expr::Get { .. } | Expr::Index { .. } | Expr::Slice { .. } => {
+                // a short-circuited optional chain evaluates to nil as a whole
+                Ok(self.evaluate_chain(expr)?.unwrap_or(ExprValue::Nil))
+            }
+            Expr::Conditional {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                // only the chosen branch is evaluated
+                if self.is_truthy(&self.evaluate(condition)?) {
+                    self.evaluate(then_branch)
+                } else {
+                    self.evaluate(else_branch)
+                }
+            }
             Expr::Unary { operator, right } => self.evaluate_unary(operator, right),
             Expr::Binary {
                 operator,
@@ -160,29 +927,636 @@ impl Interpreter {
                 left,
             } => self.evaluate_binary(operator, left, right),
             //
-            Expr::Variable(name) => self.environment.borrow().get(name),
+            Expr::SetIndex {
+                object,
+                bracket,
+                index,
+                value,
+            } => {
+                let (object, index) = (self.evaluate(object)?, self.evaluate(index)?);
+                let value = self.evaluate(value)?;
+
+                self.update_index(&object, bracket, index, |_| Ok(value.clone()))?;
+                Ok(value)
+            }
+            Expr::Variable(name) => self.env().borrow().get(name),
             Expr::Assign(name, val) => {
                 let val = self.evaluate(val)?;
-                self.environment.borrow_mut().assign(name, val.clone())?;
+                self.env().borrow_mut().assign(name, val.clone())?;
                 Ok(val)
             }
+            Expr::AssignPattern { pattern, value, .. } => {
+                let value = self.evaluate(value)?;
+
+                // nothing is assigned unless the value fits and every target can be
+                let mut bindings = Vec::new();
+                self.destructure(pattern, value.clone(), &mut bindings)?;
+
+                let environment = self.env();
+                for (name, _) in &bindings {
+                    environment.borrow().check_assign(name)?;
+                }
+
+                let mut environment = environment.borrow_mut();
+                for (name, value) in bindings {
+                    environment.assign(name, value)?;
+                }
+                Ok(value)
+            }
+            Expr::CompoundAssign {
+                target,
+                operator,
+                value,
+            } => {
+                // left to right: the target's object and index, its current value, then
+                // the right-hand side
+                let operator = self.compound_operator(operator);
+                let (_, new_val) = self.update_target(target, &operator, |current| {
+                    let value = self.evaluate(value)?;
+                    self.apply_binary(&operator, current.clone(), value)
+                })?;
+                Ok(new_val)
+            }
+            Expr::Increment {
+                target,
+                operator,
+                postfix,
+            } => {
+                let operator = self.compound_operator(operator);
+                let (old_val, new_val) =
+                    self.update_target(target, &operator, |current| match current {
+                        ExprValue::Int(_) | ExprValue::Number(_) => {
+                            self.apply_binary(&operator, current.clone(), ExprValue::Int(1))
+                        }
+                        _ => Err(RuntimeError::new(
+                            &operator.lexeme,
+                            "Operand must be a number.",
+                            operator.line,
+                        )),
+                    })?;
+                Ok(if *postfix { old_val } else { new_val })
+            }
         }
     }
 
+    /// Replaces the target's value with `update(current)` using a single lookup of the
+    /// target, returning its old and new values.
+    fn update_target<F>(
+        &self,
+        target: &Expr,
+        operator: &Token,
+        update: F,
+    ) -> Result<(ExprValue, ExprValue), RuntimeError>
+    where
+        F: FnOnce(&ExprValue) -> Result<ExprValue, RuntimeError>,
+    {
+        match target {
+            Expr::Variable(name) => Environment::update(&self.env(), name, update),
+            Expr::Index {
+                object,
+                bracket,
+                index,
+            } => {
+                let (object, index) = (self.evaluate(object)?, self.evaluate(index)?);
+                self.update_index(&object, bracket, index, update)
+            }
+            _ => Err(RuntimeError::new(
+                &operator.lexeme,
+                "Invalid assignment target.",
+                operator.line,
+            )),
+        }
+    }
+
+    /// The arithmetic operator behind `+=`, `++` and friends, keeping the original
+    /// lexeme for error messages.
+    fn compound_operator(&self, operator: &Token) -> Token {
+        let token_type = match operator.token_type {
+            TokenType::PLUSEQUAL | TokenType::PLUSPLUS => TokenType::PLUS,
+            TokenType::MINUSEQUAL | TokenType::MINUSMINUS => TokenType::MINUS,
+            TokenType::STAREQUAL => TokenType::STAR,
+            TokenType::SLASHEQUAL => TokenType::SLASH,
+            _ => unreachable!("use with compound assignment operators only!"),
+        };
+
+        Token {
+            token_type,
+            ..operator.clone()
+        }
+    }
+
+    fn evaluate_logical(
+        &self,
+        operator: &Token,
+        left: &Expr,
+        right: &Expr,
+    ) -> Result<ExprValue, RuntimeError> {
+        let left = self.evaluate(left)?;
+
+        match operator.token_type {
+            // unlike a truthiness check, `??` keeps a legitimate `false`
+            TokenType::QUESTIONQUESTION => match left {
+                ExprValue::Nil => self.evaluate(right),
+                left => Ok(left),
+            },
+            _ => Err(RuntimeError::new(
+                &operator.lexeme,
+                "Unrecognized logical operator.",
+                operator.line,
+            )),
+        }
+    }
+
+    /// Evaluates a chain of calls and property accesses, returning `None` when a `?.`
+    /// link met `nil` so the rest of the chain is skipped.
+    fn evaluate_chain(&self, expr: &Expr) -> Result<Option<ExprValue>, RuntimeError> {
+        match expr {
+            Expr::Get {
+                object,
+                name,
+                optional,
+            } => {
+                let object = match self.evaluate_chain(object)? {
+                    Some(ExprValue::Nil) if *optional => return Ok(None),
+                    Some(object) => object,
+                    None => return Ok(None),
+                };
+
+                self.get_property(&object, name).map(Some)
+            }
+            Expr::Call {
+                callee,
+                paren,
+                arguments,
+                optional,
+            } => {
+                let callee = match self.evaluate_chain(callee)? {
+                    Some(ExprValue::Nil) if *optional => return Ok(None),
+                    Some(callee) => callee,
+                    None => return Ok(None),
+                };
+
+                let arguments = arguments
+                    .iter()
+                    .map(|argument| self.evaluate(argument))
+                    .collect::<Result<Vec<ExprValue>, RuntimeError>>()?;
+
+                self.call_value(&callee, paren, arguments).map(Some)
+            }
+            Expr::Index {
+                object,
+                bracket,
+                index,
+            } => {
+                let object = match self.evaluate_chain(object)? {
+                    Some(object) => object,
+                    None => return Ok(None),
+                };
+                let index = self.evaluate(index)?;
+
+                self.get_index(&object, bracket, &index).map(Some)
+            }
+            Expr::Slice {
+                object,
+                bracket,
+                start,
+                end,
+            } => {
+                let object = match self.evaluate_chain(object)? {
+                    Some(object) => object,
+                    None => return Ok(None),
+                };
+                let start = start
+                    .as_ref()
+                    .map(|start| self.evaluate(start))
+                    .transpose()?;
+                let end = end.as_ref().map(|end| self.evaluate(end)).transpose()?;
+
+                self.slice(&object, bracket, start, end).map(Some)
+            }
+            _ => self.evaluate(expr).map(Some),
+        }
+    }
+
+    fn get_index(
+        &self,
+        object: &ExprValue,
+        bracket: &Token,
+        index: &ExprValue,
+    ) -> Result<ExprValue, RuntimeError> {
+        match object {
+            ExprValue::List(list) => {
+                let list = list.borrow();
+                let index = self.sequence_index(bracket, Type::List, list.len(), index)?;
+                Ok(list[index].clone())
+            }
+            ExprValue::String(s) => {
+                let chars = s.chars().collect::<Vec<char>>();
+                let index = self.sequence_index(bracket, Type::String, chars.len(), index)?;
+                Ok(ExprValue::String(chars[index].to_string()))
+            }
+            // a missing key reads as nil, which pairs with `??` for defaults
+            ExprValue::Map(map) => {
+                let key = self.check_map_key(bracket, index)?;
+                Ok(map.borrow().get(&key).cloned().unwrap_or(ExprValue::Nil))
+            }
+            _ => Err(self.not_indexable_error(bracket)),
+        }
+    }
+
+    /// Replaces the element at `index` with `update(current)`, returning its old and new
+    /// values. Assigning to a missing map key inserts it.
+    fn update_index<F>(
+        &self,
+        object: &ExprValue,
+        bracket: &Token,
+        index: ExprValue,
+        update: F,
+    ) -> Result<(ExprValue, ExprValue), RuntimeError>
+    where
+        F: FnOnce(&ExprValue) -> Result<ExprValue, RuntimeError>,
+    {
+        match object {
+            // the list isn't borrowed while `update` runs, which may read it itself
+            ExprValue::List(list) => {
+                let old_val = {
+                    let list = list.borrow();
+                    let position = self.sequence_index(bracket, Type::List, list.len(), &index)?;
+                    list[position].clone()
+                };
+                let new_val = update(&old_val)?;
+
+                // the update may have resized the list
+                let mut list = list.borrow_mut();
+                let position = self.sequence_index(bracket, Type::List, list.len(), &index)?;
+                list[position] = new_val.clone();
+                Ok((old_val, new_val))
+            }
+            // likewise for a map
+            ExprValue::Map(map) => {
+                let key = self.check_map_key(bracket, &index)?;
+                let old_val = map.borrow().get(&key).cloned().unwrap_or(ExprValue::Nil);
+                let new_val = update(&old_val)?;
+                map.borrow_mut().insert(key, index, new_val.clone());
+                Ok((old_val, new_val))
+            }
+            ExprValue::String(_) => Err(RuntimeError::new(
+                &bracket.lexeme,
+                "Strings are immutable.",
+                bracket.line,
+            )),
+            _ => Err(self.not_indexable_error(bracket)),
+        }
+    }
+
+    /// Slices a string (by character) or a list into a new value. Bounds may be
+    /// negative and are clamped to the sequence, so out-of-range slices are just shorter.
+    fn slice(
+        &self,
+        object: &ExprValue,
+        bracket: &Token,
+        start: Option<ExprValue>,
+        end: Option<ExprValue>,
+    ) -> Result<ExprValue, RuntimeError> {
+        let len = match object {
+            ExprValue::String(s) => s.chars().count(),
+            ExprValue::List(list) => list.borrow().len(),
+            _ => {
+                return Err(RuntimeError::new(
+                    &bracket.lexeme,
+                    "Only strings and lists can be sliced.",
+                    bracket.line,
+                ))
+            }
+        };
+
+        let bound = |bound: Option<ExprValue>, default: usize| match bound {
+            None | Some(ExprValue::Nil) => Ok(default),
+            Some(ExprValue::Int(n)) if n < 0 => Ok(len.saturating_sub(n.unsigned_abs() as usize)),
+            Some(ExprValue::Int(n)) => Ok(len.min(n as usize)),
+            Some(_) => Err(RuntimeError::new(
+                &bracket.lexeme,
+                "Slice bounds must be integers.",
+                bracket.line,
+            )),
+        };
+        let start = bound(start, 0)?;
+        let end = bound(end, len)?.max(start);
+
+        match object {
+            ExprValue::String(s) => Ok(ExprValue::String(
+                s.chars().skip(start).take(end - start).collect(),
+            )),
+            ExprValue::List(list) => Ok(ExprValue::List(Rc::new(RefCell::new(
+                list.borrow()[start..end].to_vec(),
+            )))),
+            _ => unreachable!("only strings and lists reach slicing!"),
+        }
+    }
+
+    fn check_map_key(&self, token: &Token, key: &ExprValue) -> Result<MapKey, RuntimeError> {
+        MapKey::from_value(key).ok_or_else(|| {
+            RuntimeError::new(
+                &token.lexeme,
+                format!(
+                    "Map keys must be strings, numbers, booleans or nil; got {}.",
+                    key.type_name()
+                ),
+                token.line,
+            )
+        })
+    }
+
+    /// Resolves a (possibly negative) sequence index to a position inside `0..len`.
+    // the position `index` names in a list or string (`kind`) of length `len`
+    fn sequence_index(
+        &self,
+        bracket: &Token,
+        kind: Type,
+        len: usize,
+        index: &ExprValue,
+    ) -> Result<usize, RuntimeError> {
+        let ExprValue::Int(index) = *index else {
+            return Err(RuntimeError::new(
+                &bracket.lexeme,
+                format!("{kind} index must be an integer."),
+                bracket.line,
+            ));
+        };
+
+        let position = if index < 0 {
+            index.checked_add(len as i64)
+        } else {
+            Some(index)
+        };
+
+        match position {
+            Some(position) if position >= 0 && (position as usize) < len => Ok(position as usize),
+            _ => Err(RuntimeError::new(
+                &bracket.lexeme,
+                format!(
+                    "Index out of range: {index} for {} of length {len}.",
+                    kind.name().to_lowercase()
+                ),
+                bracket.line,
+            )),
+        }
+    }
+
+    fn not_indexable_error(&self, bracket: &Token) -> RuntimeError {
+        RuntimeError::new(
+            &bracket.lexeme,
+            "Only lists, maps and strings can be indexed.",
+            bracket.line,
+        )
+    }
+
+    fn get_property(&self, object: &ExprValue, name: &Token) -> Result<ExprValue, RuntimeError> {
+        match object {
+            ExprValue::Error(err) if name.lexeme == "message" => {
+                Ok(ExprValue::String(err.message.clone()))
+            }
+            ExprValue::Error(err) if name.lexeme == "line" => Ok(ExprValue::Int(err.line as i64)),
+            ExprValue::Module(module) => {
+                let value = module.environment.borrow().get_own(&name.lexeme);
+                value.ok_or_else(|| {
+                    RuntimeError::new(
+                        &name.lexeme,
+                        format!(
+                            "Undefined name '{}' in module '{}'.",
+                            name.lexeme, module.name
+                        ),
+                        name.line,
+                    )
+                })
+            }
+            ExprValue::Enum(enumeration) => native::enum_property(enumeration, &name.lexeme)
+                .ok_or_else(|| {
+                    RuntimeError::new(
+                        &name.lexeme,
+                        format!(
+                            "Undefined variant '{}' in enum '{}'.",
+                            name.lexeme, enumeration.name
+                        ),
+                        name.line,
+                    )
+                }),
+            ExprValue::Variant(variant) => variant.field(&name.lexeme).ok_or_else(|| {
+                RuntimeError::new(
+                    &name.lexeme,
+                    format!(
+                        "Undefined field '{}' on {}.{}.",
+                        name.lexeme,
+                        variant.owner.name,
+                        variant.name()
+                    ),
+                    name.line,
+                )
+            }),
+            ExprValue::Range(range) => {
+                native::range_property(*range, &name.lexeme).ok_or_else(|| {
+                    RuntimeError::new(
+                        &name.lexeme,
+                        format!("Undefined property '{}' on Range.", name.lexeme),
+                        name.line,
+                    )
+                })
+            }
+            ExprValue::Generator(generator) => native::generator_property(generator, &name.lexeme)
+                .ok_or_else(|| {
+                    RuntimeError::new(
+                        &name.lexeme,
+                        format!("Undefined property '{}' on Generator.", name.lexeme),
+                        name.line,
+                    )
+                }),
+            ExprValue::String(s) => native::string_property(s, &name.lexeme).ok_or_else(|| {
+                RuntimeError::new(
+                    &name.lexeme,
+                    format!("Undefined property '{}' on String.", name.lexeme),
+                    name.line,
+                )
+            }),
+            _ => Err(RuntimeError::new(
+                &name.lexeme,
+                "Only instances have properties.",
+                name.line,
+            )),
+        }
+    }
+
+    pub(crate) fn call_value(
+        &self,
+        callee: &ExprValue,
+        paren: &Token,
+        arguments: Vec<ExprValue>,
+    ) -> Result<ExprValue, RuntimeError> {
+        match callee {
+            ExprValue::NativeFunction(function) => {
+                if arguments.len() != function.arity {
+                    return Err(RuntimeError::new(
+                        &paren.lexeme,
+                        format!(
+                            "Expected {} arguments but got {}.",
+                            function.arity,
+                            arguments.len()
+                        ),
+                        paren.line,
+                    ));
+                }
+
+                function.call(&arguments).map_err(|err| match err {
+                    NativeError::Message(message) => {
+                        RuntimeError::new(&paren.lexeme, message, paren.line)
+                    }
+                    NativeError::Runtime(err) => err,
+                })
+            }
+            ExprValue::Function(function) => {
+                let (min, max) = function.arity();
+                let expected = match max {
+                    Some(max) if max == min => min.to_string(),
+                    Some(max) => format!("{min} to {max}"),
+                    None => format!("at least {min}"),
+                };
+
+                if arguments.len() < min || max.is_some_and(|max| arguments.len() > max) {
+                    return Err(RuntimeError::new(
+                        &paren.lexeme,
+                        format!("Expected {expected} arguments but got {}.", arguments.len()),
+                        paren.line,
+                    ));
+                }
+
+                self.call_function(function, paren, arguments)
+            }
+            _ => Err(RuntimeError::new(
+                &paren.lexeme,
+                "Can only call functions and classes.",
+                paren.line,
+            )),
+        }
+    }
+
+    fn call_function(
+        &self,
+        function: &Function,
+        paren: &Token,
+        arguments: Vec<ExprValue>,
+    ) -> Result<ExprValue, RuntimeError> {
+        if self.depth.get() == MAX_CALL_DEPTH {
+            return Err(RuntimeError::new(
+                &paren.lexeme,
+                "Stack overflow.",
+                paren.line,
+            ));
+        }
+
+        // the body runs over the call's scope and, like a module's top level, never
+        // echoes expressions
+        let scope = Rc::new(RefCell::new(Environment::with_enclosing(
+            function.closure.clone(),
+        )));
+        let prev_env = self.env();
+        let prev_status = self.status.replace(InterpreterStatus::Run);
+        self.set_env(scope.clone());
+        self.depth.set(self.depth.get() + 1);
+
+        let result = self.run_function(function, arguments, scope);
+
+        self.depth.set(self.depth.get() - 1);
+        self.set_env(prev_env);
+        self.status.set(prev_status);
+        result
+    }
+
+    // runs `function` in `scope`, which is already the current scope
+    fn run_function(
+        &self,
+        function: &Function,
+        arguments: Vec<ExprValue>,
+        scope: Rc<RefCell<Environment>>,
+    ) -> Result<ExprValue, RuntimeError> {
+        // parameters are bound in order, so a default can refer to earlier parameters
+        let mut arguments = arguments.into_iter();
+        for param in &function.params {
+            let value = match (param.rest, arguments.next(), &param.default) {
+                (true, first, _) => {
+                    let rest = first.into_iter().chain(arguments.by_ref()).collect();
+                    ExprValue::List(Rc::new(RefCell::new(rest)))
+                }
+                (false, Some(argument), _) => argument,
+                (false, None, Some(default)) => self.evaluate(default)?,
+                (false, None, None) => unreachable!("arity is checked before the call!"),
+            };
+
+            scope.borrow_mut().define(param.name.lexeme.clone(), value);
+        }
+
+        // a generator's body doesn't start until the generator is first resumed, so it
+        // gets an interpreter of its own that keeps its place between resumes
+        if function.generator {
+            let interpreter = Interpreter {
+                environment: RefCell::new(scope.clone()),
+                status: Cell::new(InterpreterStatus::Run),
+                script_path: self.script_path.clone(),
+                modules: self.modules.clone(),
+                depth: self.depth.clone(),
+            };
+            let generator = Generator::new(
+                function.name.clone(),
+                interpreter,
+                scope,
+                function.body.clone(),
+            );
+            return Ok(ExprValue::Generator(Rc::new(RefCell::new(generator))));
+        }
+
+        for stmt in function.body.iter() {
+            match self.execute(stmt) {
+                Ok(()) => (),
+                Err(Unwind::Return(value)) => return Ok(value),
+                Err(Unwind::Error(err)) => return Err(err),
+                // the parser doesn't let loop control cross a function boundary
+                Err(Unwind::Break(_) | Unwind::Continue(_)) => {
+                    unreachable!("loop control should not escape a function!")
+                }
+            }
+        }
+
+        Ok(ExprValue::Nil)
+    }
+
     fn evaluate_unary(&self, operator: &Token, right: &Expr) -> Result<ExprValue, RuntimeError> {
         let right = self.evaluate(right)?;
 
         match operator.token_type {
             TokenType::BANG => Ok(ExprValue::Bool(!self.is_truthy(&right))),
-            TokenType::MINUS => {
-                let expr_num = self.check_num_operand(operator, &right)?;
-                Ok(ExprValue::Number(-expr_num))
-            }
-            _ => Err(RuntimeError {
-                token: operator.to_string(),
-                message: "Invalid operator found in unary expression".to_string(),
-                line: operator.line,
-            }),
+            TokenType::MINUS => match right {
+                ExprValue::Int(n) => n
+                    .checked_neg()
+                    .map(ExprValue::Int)
+                    .ok_or_else(|| self.overflow_error(operator)),
+                ExprValue::Number(n) => Ok(ExprValue::Number(-n)),
+                _ => Err(RuntimeError::new(
+                    &operator.lexeme,
+                    "Operand must be a number.",
+                    operator.line,
+                )),
+            },
+            TokenType::TILDE => match right {
+                ExprValue::Int(n) => Ok(ExprValue::Int(!n)),
+                _ => Err(RuntimeError::new(
+                    &operator.lexeme,
+                    "Operand must be an integer.",
+                    operator.line,
+                )),
+            },
+            _ => Err(RuntimeError::new(
+                operator.to_string(),
+                "Invalid operator found in unary expression",
+                operator.line,
+            )),
         }
     }
 
@@ -195,72 +1569,144 @@ impl Interpreter {
         let left = self.evaluate(left)?;
         let right = self.evaluate(right)?;
 
+        self.apply_binary(operator, left, right)
+    }
+
+    fn apply_binary(
+        &self,
+        operator: &Token,
+        left: ExprValue,
+        right: ExprValue,
+    ) -> Result<ExprValue, RuntimeError> {
         match operator.token_type {
             TokenType::GREATER => {
-                let (left, right) = self.check_num_operands(operator, &left, &right)?;
-                Ok(ExprValue::Bool(left > right))
+                let operands = self.check_num_operands(operator, &left, &right)?;
+                Ok(ExprValue::Bool(
+                    operands.compare() == Some(Ordering::Greater),
+                ))
             }
             TokenType::GREATEREQUAL => {
-                let (left, right) = self.check_num_operands(operator, &left, &right)?;
-                Ok(ExprValue::Bool(left >= right))
+                let operands = self.check_num_operands(operator, &left, &right)?;
+                Ok(ExprValue::Bool(matches!(
+                    operands.compare(),
+                    Some(Ordering::Greater | Ordering::Equal)
+                )))
             }
             TokenType::LESS => {
-                let (left, right) = self.check_num_operands(operator, &left, &right)?;
-                Ok(ExprValue::Bool(left < right))
+                let operands = self.check_num_operands(operator, &left, &right)?;
+                Ok(ExprValue::Bool(operands.compare() == Some(Ordering::Less)))
             }
             TokenType::LESSEQUAL => {
-                let (left, right) = self.check_num_operands(operator, &left, &right)?;
-                Ok(ExprValue::Bool(left <= right))
+                let operands = self.check_num_operands(operator, &left, &right)?;
+                Ok(ExprValue::Bool(matches!(
+                    operands.compare(),
+                    Some(Ordering::Less | Ordering::Equal)
+                )))
             }
             TokenType::MINUS => {
-                let (left, right) = self.check_num_operands(operator, &left, &right)?;
-                Ok(ExprValue::Number(left - right))
+                let operands = self.check_num_operands(operator, &left, &right)?;
+                self.eval_arithmetic(operator, operands, i64::checked_sub, |l, r| l - r)
             }
             TokenType::PLUS => match (left, right) {
-                (ExprValue::Number(left), ExprValue::Number(right)) => {
-                    Ok(ExprValue::Number(left + right))
-                }
                 (ExprValue::String(left), ExprValue::String(right)) => {
                     let expr_val = left + &right;
                     Ok(ExprValue::String(expr_val))
                 }
-                _ => Err(RuntimeError {
-                    token: operator.lexeme.to_string(),
-                    message: "Operands must be two numbers or two strings.".to_string(),
-                    line: operator.line,
-                }),
+                (left, right) => match NumOperands::promote(&left, &right) {
+                    Some(operands) => {
+                        self.eval_arithmetic(operator, operands, i64::checked_add, |l, r| l + r)
+                    }
+                    None => Err(RuntimeError::new(
+                        &operator.lexeme,
+                        "Operands must be two numbers or two strings.",
+                        operator.line,
+                    )),
+                },
+            },
+            // always true division, even of two integers; `~/` is the integer one
+            TokenType::SLASH => match self.check_num_operands(operator, &left, &right)? {
+                NumOperands::Int(left, right) => Ok(ExprValue::Number(left as f64 / right as f64)),
+                NumOperands::Float(left, right) => Ok(ExprValue::Number(left / right)),
             },
-            TokenType::SLASH => {
-                let (left, right) = self.check_num_operands(operator, &left, &right)?;
-                Ok(ExprValue::Number(left / right))
-            }
             TokenType::STAR => {
-                let (left, right) = self.check_num_operands(operator, &left, &right)?;
-                Ok(ExprValue::Number(left * right))
+                let operands = self.check_num_operands(operator, &left, &right)?;
+                self.eval_arithmetic(operator, operands, i64::checked_mul, |l, r| l * r)
+            }
+            TokenType::PERCENT => {
+                let operands = self.check_num_operands(operator, &left, &right)?;
+                if let NumOperands::Int(_, 0) = operands {
+                    return Err(self.division_by_zero_error(operator));
+                }
+                // remainder takes the sign of the dividend
+                self.eval_arithmetic(operator, operands, i64::checked_rem, |l, r| l % r)
+            }
+            TokenType::TILDESLASH => {
+                let operands = self.check_num_operands(operator, &left, &right)?;
+                if let NumOperands::Int(_, 0) = operands {
+                    return Err(self.division_by_zero_error(operator));
+                }
+                self.eval_arithmetic(operator, operands, checked_floor_div, |l, r| {
+                    (l / r).floor()
+                })
+            }
+            TokenType::STARSTAR => match self.check_num_operands(operator, &left, &right)? {
+                // a negative exponent can't stay integral, so fall back to floats
+                NumOperands::Int(base, exp) if exp < 0 => {
+                    Ok(ExprValue::Number((base as f64).powf(exp as f64)))
+                }
+                NumOperands::Int(base, exp) => u32::try_from(exp)
+                    .ok()
+                    .and_then(|exp| base.checked_pow(exp))
+                    .map(ExprValue::Int)
+                    .ok_or_else(|| self.overflow_error(operator)),
+                NumOperands::Float(base, exp) => Ok(ExprValue::Number(base.powf(exp))),
+            },
+            TokenType::AMPERSAND => {
+                let (left, right) = self.check_int_operands(operator, &left, &right)?;
+                Ok(ExprValue::Int(left & right))
+            }
+            TokenType::PIPE => {
+                let (left, right) = self.check_int_operands(operator, &left, &right)?;
+                Ok(ExprValue::Int(left | right))
+            }
+            TokenType::CARET => {
+                let (left, right) = self.check_int_operands(operator, &left, &right)?;
+                Ok(ExprValue::Int(left ^ right))
+            }
+            TokenType::LESSLESS => {
+                let (left, right) = self.check_int_operands(operator, &left, &right)?;
+                let shift = self.check_shift_amount(operator, right)?;
+                Ok(ExprValue::Int(left << shift))
+            }
+            TokenType::GREATERGREATER => {
+                // arithmetic shift, so negative numbers keep their sign
+                let (left, right) = self.check_int_operands(operator, &left, &right)?;
+                let shift = self.check_shift_amount(operator, right)?;
+                Ok(ExprValue::Int(left >> shift))
             }
             TokenType::NOTEQUAL => Ok(ExprValue::Bool(!self.is_equal(&left, &right))),
             TokenType::EQUAL => Ok(ExprValue::Bool(self.is_equal(&left, &right))),
-            _ => Err(RuntimeError {
-                token: operator.lexeme.to_string(),
-                message: "Unrecognized binary operator.".to_string(),
-                line: operator.line,
-            }),
+            _ => Err(RuntimeError::new(
+                &operator.lexeme,
+                "Unrecognized binary operator.",
+                operator.line,
+            )),
         }
     }
 
     // helpers
-    fn check_num_operand(
+    fn eval_arithmetic(
         &self,
         operator: &Token,
-        expr_val: &ExprValue,
-    ) -> Result<f64, RuntimeError> {
-        match expr_val {
-            ExprValue::Number(n) => Ok(*n),
-            _ => Err(RuntimeError {
-                token: operator.lexeme.to_string(),
-                message: "Operand must be a number.".to_string(),
-                line: operator.line,
-            }),
+        operands: NumOperands,
+        int_op: fn(i64, i64) -> Option<i64>,
+        float_op: fn(f64, f64) -> f64,
+    ) -> Result<ExprValue, RuntimeError> {
+        match operands {
+            NumOperands::Int(left, right) => int_op(left, right)
+                .map(ExprValue::Int)
+                .ok_or_else(|| self.overflow_error(operator)),
+            NumOperands::Float(left, right) => Ok(ExprValue::Number(float_op(left, right))),
         }
     }
 
@@ -269,18 +1715,69 @@ impl Interpreter {
         operator: &Token,
         left: &ExprValue,
         right: &ExprValue,
-    ) -> Result<(f64, f64), RuntimeError> {
+    ) -> Result<NumOperands, RuntimeError> {
+        NumOperands::promote(left, right).ok_or_else(|| {
+            RuntimeError::new(&operator.lexeme, "Operands must be numbers", operator.line)
+        })
+    }
+
+    fn check_int_operands(
+        &self,
+        operator: &Token,
+        left: &ExprValue,
+        right: &ExprValue,
+    ) -> Result<(i64, i64), RuntimeError> {
         match (left, right) {
-            (ExprValue::Number(left), ExprValue::Number(right)) => Ok((*left, *right)),
-            _ => Err(RuntimeError {
-                token: operator.lexeme.to_string(),
-                message: "Operands must be numbers".to_string(),
-                line: operator.line,
-            }),
+            (ExprValue::Int(left), ExprValue::Int(right)) => Ok((*left, *right)),
+            _ => Err(RuntimeError::new(
+                &operator.lexeme,
+                "Operands must be integers.",
+                operator.line,
+            )),
+        }
+    }
+
+    fn check_shift_amount(&self, operator: &Token, amount: i64) -> Result<u32, RuntimeError> {
+        match u32::try_from(amount) {
+            Ok(amount) if amount < i64::BITS => Ok(amount),
+            _ => Err(RuntimeError::new(
+                &operator.lexeme,
+                format!("Shift amount must be between 0 and {}.", i64::BITS - 1),
+                operator.line,
+            )),
+        }
+    }
+
+    fn overflow_error(&self, operator: &Token) -> RuntimeError {
+        RuntimeError::new(&operator.lexeme, "Integer overflow.", operator.line)
+    }
+
+    fn division_by_zero_error(&self, operator: &Token) -> RuntimeError {
+        RuntimeError::new(&operator.lexeme, "Division by zero.", operator.line)
+    }
+
+    // the type names are the ones annotations use, so an Int is also a Number and
+    // everything is Any; any other name must be an enum in scope, which its variants
+    // are values of. Without classes there are no instance or subclass tests yet.
+    fn is_type(&self, value: &ExprValue, type_name: &Token) -> Result<bool, RuntimeError> {
+        if let Some(ty) = Type::from_name(&type_name.lexeme) {
+            return Ok(ty.accepts(value.value_type()));
+        }
+
+        match (self.env().borrow().get(type_name), value) {
+            (Ok(ExprValue::Enum(enumeration)), ExprValue::Variant(variant)) => {
+                Ok(Rc::ptr_eq(&enumeration, &variant.owner))
+            }
+            (Ok(ExprValue::Enum(_)), _) => Ok(false),
+            _ => Err(RuntimeError::new(
+                &type_name.lexeme,
+                format!("Unknown type '{}'.", type_name.lexeme),
+                type_name.line,
+            )),
         }
     }
 
-    fn is_truthy(&self, expr_val: &ExprValue) -> bool {
+    pub(crate) fn is_truthy(&self, expr_val: &ExprValue) -> bool {
         match expr_val {
             ExprValue::Nil => false,
             ExprValue::Bool(b) => *b,
@@ -289,12 +1786,98 @@ impl Interpreter {
     }
 
     fn is_equal(&self, left: &ExprValue, right: &ExprValue) -> bool {
-        match (left, right) {
+        self.values_equal(left, right, &mut Vec::new())
+    }
+
+    // `open` holds the pairs of lists and maps being compared, so a collection that
+    // contains itself ends the recursion: a pair met again is taken to be equal, and
+    // the comparison is decided by the rest of their elements
+    fn values_equal(
+        &self,
+        left: &ExprValue,
+        right: &ExprValue,
+        open: &mut Vec<(*const (), *const ())>,
+    ) -> bool {
+        let pair = match (left, right) {
+            (ExprValue::List(a), ExprValue::List(b)) => {
+                Some((a.as_ptr() as *const (), b.as_ptr() as *const ()))
+            }
+            (ExprValue::Map(a), ExprValue::Map(b)) => {
+                Some((a.as_ptr() as *const (), b.as_ptr() as *const ()))
+            }
+            _ => None,
+        };
+        if let Some(pair) = pair {
+            if open.contains(&pair) {
+                return true;
+            }
+            open.push(pair);
+        }
+
+        let equal = match (left, right) {
             (ExprValue::Nil, ExprValue::Nil) => true,
             (ExprValue::Bool(a), ExprValue::Bool(b)) => a == b,
-            (ExprValue::Number(a), ExprValue::Number(b)) => (a - b).abs() < f64::EPSILON,
+            (ExprValue::Int(a), ExprValue::Int(b)) => a == b,
+            (ExprValue::Number(a), ExprValue::Number(b)) => a == b,
+            (ExprValue::Int(a), ExprValue::Number(b))
+            | (ExprValue::Number(b), ExprValue::Int(a)) => {
+                // only integral floats inside i64's range can equal an int
+                b.fract() == 0.0
+                    && *b >= i64::MIN as f64
+                    && *b < -(i64::MIN as f64)
+                    && *a == *b as i64
+            }
             (ExprValue::String(a), ExprValue::String(b)) => a == b,
+            (ExprValue::Range(a), ExprValue::Range(b)) => a == b,
+            // functions are only equal to themselves, as are the other values that
+            // have an identity of their own
+            (ExprValue::Function(a), ExprValue::Function(b)) => Rc::ptr_eq(a, b),
+            (ExprValue::NativeFunction(a), ExprValue::NativeFunction(b)) => Rc::ptr_eq(a, b),
+            (ExprValue::Generator(a), ExprValue::Generator(b)) => Rc::ptr_eq(a, b),
+            (ExprValue::Module(a), ExprValue::Module(b)) => Rc::ptr_eq(a, b),
+            (ExprValue::Error(a), ExprValue::Error(b)) => Rc::ptr_eq(a, b),
+            // as are enums, so like-named variants of different enums differ
+            (ExprValue::Enum(a), ExprValue::Enum(b)) => Rc::ptr_eq(a, b),
+            (ExprValue::Variant(a), ExprValue::Variant(b)) => {
+                Rc::ptr_eq(&a.owner, &b.owner)
+                    && a.index == b.index
+                    && a.payload
+                        .iter()
+                        .zip(b.payload.iter())
+                        .all(|(a, b)| self.values_equal(a, b, open))
+            }
+            (ExprValue::List(a), ExprValue::List(b)) => {
+                let (a, b) = (a.borrow(), b.borrow());
+                a.len() == b.len()
+                    && a.iter()
+                        .zip(b.iter())
+                        .all(|(a, b)| self.values_equal(a, b, open))
+            }
+            (ExprValue::Map(a), ExprValue::Map(b)) => {
+                let (a, b) = (a.borrow(), b.borrow());
+                a.len() == b.len()
+                    && a.iter().all(|(key, value)| {
+                        MapKey::from_value(key)
+                            .and_then(|key| b.get(&key))
+                            .is_some_and(|other| self.values_equal(value, other, open))
+                    })
+            }
             _ => false,
+        };
+
+        if pair.is_some() {
+            open.pop();
         }
+        equal
+    }
+}
+
+fn checked_floor_div(left: i64, right: i64) -> Option<i64> {
+    let quotient = left.checked_div(right)?;
+
+    if left % right != 0 && (left < 0) != (right < 0) {
+        Some(quotient - 1)
+    } else {
+        Some(quotient)
     }
 }
@@ -0,0 +1,145 @@
+use crate::{ExprValue, Generator, Interpreter, Map, MapKey, Range, RuntimeError, Token};
+use std::{cell::RefCell, rc::Rc};
+
+/// Where a `for-in` loop is in the value it iterates over.
+///
+/// Strings, lists, ranges and maps (by key) iterate natively, as do generators. A map
+/// passed to `iterable()` takes part through the iteration protocol instead: one with
+/// an `iterator` function is iterable through the iterator that function returns, and
+/// one with a `next` function is itself an iterator, whose `next()` returns `nil` once
+/// it's exhausted.
+#[derive(Debug)]
+pub enum Iteration {
+    /// Reads the list as it goes, so elements appended during the loop are visited.
+    List {
+        list: Rc<RefCell<Vec<ExprValue>>>,
+        index: usize,
+    },
+    Chars(std::vec::IntoIter<char>),
+    Range {
+        range: Range,
+        index: u128,
+    },
+    /// The keys as they were when the loop started.
+    Keys(std::vec::IntoIter<ExprValue>),
+    Generator(Rc<RefCell<Generator>>),
+    /// A user-defined iterator's `next` function.
+    Next(ExprValue),
+}
+
+/// Whether a `break`/`continue` aimed at `target` (the innermost loop when `None`)
+/// stops at the loop labeled `label`.
+pub(crate) fn targets_loop(target: &Option<String>, label: &Option<String>) -> bool {
+    target.is_none() || target == label
+}
+
+impl Interpreter {
+    pub(crate) fn iterate(
+        &self,
+        iterable: ExprValue,
+        keyword: &Token,
+    ) -> Result<Iteration, RuntimeError> {
+        match iterable {
+            ExprValue::List(list) => Ok(Iteration::List { list, index: 0 }),
+            ExprValue::String(s) => Ok(Iteration::Chars(s.chars().collect::<Vec<_>>().into_iter())),
+            ExprValue::Range(range) => Ok(Iteration::Range { range, index: 0 }),
+            ExprValue::Generator(generator) => Ok(Iteration::Generator(generator)),
+            ExprValue::Map(map) if map.borrow().is_iterable() => {
+                if let Some(iterator) = protocol_function(&map.borrow(), "iterator") {
+                    let iterator = self.call_value(&iterator, keyword, Vec::new())?;
+
+                    return match iterator {
+                        ExprValue::Generator(generator) => Ok(Iteration::Generator(generator)),
+                        ExprValue::Map(ref map) => match protocol_function(&map.borrow(), "next") {
+                            Some(next) => Ok(Iteration::Next(next)),
+                            None => Err(not_iterator_error(keyword, &iterator)),
+                        },
+                        _ => Err(not_iterator_error(keyword, &iterator)),
+                    };
+                }
+
+                match protocol_function(&map.borrow(), "next") {
+                    Some(next) => Ok(Iteration::Next(next)),
+                    None => Err(RuntimeError::new(
+                        &keyword.lexeme,
+                        "An iterable map must have an 'iterator' or 'next' function.",
+                        keyword.line,
+                    )),
+                }
+            }
+            ExprValue::Map(map) => {
+                let keys = map
+                    .borrow()
+                    .iter()
+                    .map(|(key, _)| key.clone())
+                    .collect::<Vec<_>>();
+                Ok(Iteration::Keys(keys.into_iter()))
+            }
+            _ => Err(RuntimeError::new(
+                &keyword.lexeme,
+                format!(
+                    "Can only iterate over strings, lists, maps, ranges and generators; got {}.",
+                    iterable.type_name()
+                ),
+                keyword.line,
+            )),
+        }
+    }
+
+    /// The next value of the iteration, or `None` once it's exhausted.
+    pub(crate) fn next_value(
+        &self,
+        iteration: &mut Iteration,
+        keyword: &Token,
+    ) -> Result<Option<ExprValue>, RuntimeError> {
+        match iteration {
+            Iteration::List { list, index } => {
+                let value = list.borrow().get(*index).cloned();
+                *index += 1;
+                Ok(value)
+            }
+            Iteration::Chars(chars) => Ok(chars.next().map(|c| ExprValue::String(c.to_string()))),
+            Iteration::Range { range, index } => {
+                let value = range.get(*index).map(ExprValue::Int);
+                *index += 1;
+                Ok(value)
+            }
+            Iteration::Keys(keys) => Ok(keys.next()),
+            Iteration::Generator(generator) => {
+                let mut generator = generator.try_borrow_mut().map_err(|_| {
+                    RuntimeError::new(
+                        &keyword.lexeme,
+                        "Generator is already running.",
+                        keyword.line,
+                    )
+                })?;
+                generator.resume()
+            }
+            Iteration::Next(next) => match self.call_value(next, keyword, Vec::new())? {
+                ExprValue::Nil => Ok(None),
+                value => Ok(Some(value)),
+            },
+        }
+    }
+}
+
+// a map entry holding a function, which makes the map part of the iteration protocol
+fn protocol_function(map: &Map, name: &str) -> Option<ExprValue> {
+    match map.get(&MapKey::String(name.to_string())) {
+        Some(function @ (ExprValue::Function(_) | ExprValue::NativeFunction(_))) => {
+            Some(function.clone())
+        }
+        _ => None,
+    }
+}
+
+fn not_iterator_error(keyword: &Token, value: &ExprValue) -> RuntimeError {
+    RuntimeError::new(
+        &keyword.lexeme,
+        format!(
+            "'iterator' must return a generator or a map with a 'next' function; got {}.",
+            value.type_name()
+        ),
+        keyword.line,
+    )
+}
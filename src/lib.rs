@@ -1,15 +1,39 @@
+pub mod enumeration;
 pub mod environment;
 pub mod expr;
+pub mod format;
+pub mod function;
+pub mod generator;
 pub mod interpreter;
+pub mod iteration;
+pub mod map;
+pub mod module;
+pub mod native;
 pub mod parser;
+pub mod pattern;
+pub mod range;
 pub mod scanner;
 pub mod stmt;
 pub mod token;
+pub mod typecheck;
+pub mod types;
 
+pub use enumeration::{Enum, Variant};
 pub use environment::Environment;
 pub use expr::*;
-pub use interpreter::{ExprValue, Interpreter, RuntimeError};
+pub use format::Style;
+pub use function::Function;
+pub use generator::Generator;
+pub use interpreter::{ExprValue, Interpreter, RuntimeError, Unwind};
+pub use iteration::Iteration;
+pub use map::{Map, MapKey};
+pub use module::{Module, ModuleCache, ModuleState};
+pub use native::{NativeError, NativeFunction};
 pub use parser::Parser;
+pub use pattern::Pattern;
+pub use range::Range;
 pub use scanner::Scanner;
-pub use stmt::Stmt;
+pub use stmt::{EnumVariant, MatchArm, Stmt};
 pub use token::*;
+pub use typecheck::{TypeChecker, TypeError};
+pub use types::{Type, TypeAnnotation};
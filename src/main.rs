@@ -1,12 +1,31 @@
-use interpreter_starter_rust::{Interpreter, Parser, Scanner};
+use interpreter_starter_rust::{Interpreter, Parser, Scanner, TypeChecker};
 use std::env;
 use std::fs;
 use std::process;
+use std::thread;
+
+// a Lox call takes several nested Rust frames, so the interpreter gets a stack deep
+// enough to reach its own call limit even in unoptimized builds
+const STACK_SIZE: usize = 256 * 1024 * 1024;
 
 fn main() {
+    let interpreter = thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(start)
+        .expect("should be able to spawn the interpreter thread");
+
+    if interpreter.join().is_err() {
+        process::exit(101)
+    }
+}
+
+fn start() {
     let args: Vec<String> = env::args().collect();
     if args.len() < 3 {
-        eprintln!("Usage: {} tokenize | parse | evaluate <filename>", args[0]);
+        eprintln!(
+            "Usage: {} tokenize | parse | evaluate | run | typecheck <filename>",
+            args[0]
+        );
 
         return;
     }
@@ -22,8 +41,9 @@ fn main() {
     match command.as_str() {
         "tokenize" => tokenize(file_contents),
         "parse" => parse(file_contents),
-        "evaluate" => evaluate(file_contents),
-        "run" => run(file_contents),
+        "evaluate" => evaluate(file_contents, filename),
+        "run" => run(file_contents, filename),
+        "typecheck" => typecheck(file_contents),
         _ => {
             eprintln!("Unknown command: {}", command);
         }
@@ -72,7 +92,7 @@ fn parse(file_contents: String) {
     }
 }
 
-fn evaluate(file_contents: String) {
+fn evaluate(file_contents: String, filename: &str) {
     let scanner = Scanner::new(file_contents);
     let (tokens, errors) = scanner.scan_tokens();
 
@@ -89,6 +109,7 @@ fn evaluate(file_contents: String) {
     match parser.parse() {
         Ok(statements) => {
             let mut interpreter = Interpreter::new();
+            interpreter.set_script_path(filename);
             match interpreter.interpret(statements) {
                 Ok(_) => (),
                 Err(runtime_err) => {
@@ -104,7 +125,7 @@ fn evaluate(file_contents: String) {
     }
 }
 
-fn run(file_contents: String) {
+fn run(file_contents: String, filename: &str) {
     let scanner = Scanner::new(file_contents);
     let (tokens, errors) = scanner.scan_tokens();
 
@@ -121,6 +142,7 @@ fn run(file_contents: String) {
     match parser.parse() {
         Ok(statements) => {
             let mut interpreter = Interpreter::new();
+            interpreter.set_script_path(filename);
             interpreter
                 .set_status("run")
                 .expect("should set interpreter status::run");
@@ -138,3 +160,36 @@ fn run(file_contents: String) {
         }
     }
 }
+
+fn typecheck(file_contents: String) {
+    let scanner = Scanner::new(file_contents);
+    let (tokens, errors) = scanner.scan_tokens();
+
+    for error in &errors {
+        eprintln!("{}", error)
+    }
+
+    if !errors.is_empty() {
+        process::exit(65)
+    }
+
+    let mut parser = Parser::new(tokens);
+
+    match parser.parse() {
+        Ok(statements) => {
+            let type_errors = TypeChecker::new().check(&statements);
+
+            for type_err in &type_errors {
+                eprintln!("{}", type_err);
+            }
+
+            if !type_errors.is_empty() {
+                process::exit(65);
+            }
+        }
+        Err(parse_err) => {
+            eprintln!("{}", parse_err);
+            process::exit(65);
+        }
+    }
+}
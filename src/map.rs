@@ -0,0 +1,97 @@
+use crate::ExprValue;
+use std::collections::HashMap;
+
+/// The hashable form of a map key. Integral floats share the `Int` form so that
+/// `m[1]` and `m[1.0]` name the same entry, matching `==`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MapKey {
+    Nil,
+    Bool(bool),
+    Int(i64),
+    Float(u64),
+    String(String),
+}
+
+impl MapKey {
+    /// Returns `None` for values that can't be hashed, i.e. mutable containers.
+    pub fn from_value(value: &ExprValue) -> Option<MapKey> {
+        match value {
+            ExprValue::Nil => Some(MapKey::Nil),
+            ExprValue::Bool(b) => Some(MapKey::Bool(*b)),
+            ExprValue::Int(n) => Some(MapKey::Int(*n)),
+            ExprValue::Number(n) => {
+                if n.fract() == 0.0 && *n >= i64::MIN as f64 && *n < -(i64::MIN as f64) {
+                    Some(MapKey::Int(*n as i64))
+                } else {
+                    Some(MapKey::Float(n.to_bits()))
+                }
+            }
+            ExprValue::String(s) => Some(MapKey::String(s.clone())),
+            _ => None,
+        }
+    }
+}
+
+/// An insertion-ordered dictionary, so printing and iteration are deterministic.
+#[derive(Debug, Clone, Default)]
+pub struct Map {
+    entries: Vec<(ExprValue, ExprValue)>,
+    positions: HashMap<MapKey, usize>,
+    // set by `iterable()`: a `for-in` loop goes through the map's `iterator` or `next`
+    // function instead of its keys
+    iterable: bool,
+}
+
+impl Map {
+    pub fn new() -> Self {
+        Map {
+            entries: Vec::new(),
+            positions: HashMap::new(),
+            iterable: false,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get(&self, key: &MapKey) -> Option<&ExprValue> {
+        self.positions
+            .get(key)
+            .map(|position| &self.entries[*position].1)
+    }
+
+    /// Inserts or overwrites an entry, returning the previous value. An overwritten
+    /// entry keeps its original position and key.
+    pub fn insert(
+        &mut self,
+        key: MapKey,
+        key_value: ExprValue,
+        value: ExprValue,
+    ) -> Option<ExprValue> {
+        match self.positions.get(&key) {
+            Some(position) => Some(std::mem::replace(&mut self.entries[*position].1, value)),
+            None => {
+                self.positions.insert(key, self.entries.len());
+                self.entries.push((key_value, value));
+                None
+            }
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &(ExprValue, ExprValue)> {
+        self.entries.iter()
+    }
+
+    pub fn is_iterable(&self) -> bool {
+        self.iterable
+    }
+
+    pub fn mark_iterable(&mut self) {
+        self.iterable = true;
+    }
+}
@@ -0,0 +1,26 @@
+use crate::{Environment, RuntimeError};
+use std::{cell::RefCell, collections::HashMap, path::PathBuf, rc::Rc};
+
+/// An imported file. Its top-level bindings are exposed as properties of the
+/// namespace value it is bound to.
+#[derive(Debug)]
+pub struct Module {
+    pub name: String,
+    pub path: PathBuf,
+    pub environment: Rc<RefCell<Environment>>,
+}
+
+/// Modules by canonical path, shared by every interpreter in a run so each file is
+/// executed once.
+pub type ModuleCache = Rc<RefCell<HashMap<PathBuf, ModuleState>>>;
+
+#[derive(Debug, Clone)]
+pub enum ModuleState {
+    /// The module's top level is still executing: importing it again means the
+    /// imports form a cycle.
+    Loading,
+    Loaded(Rc<Module>),
+    /// The module's top level raised this error. Later imports raise it again rather
+    /// than rerunning the module's side effects.
+    Failed(RuntimeError),
+}
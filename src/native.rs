@@ -0,0 +1,378 @@
+use crate::{Enum, Environment, ExprValue, Generator, Range, RuntimeError, Variant};
+use std::{cell::RefCell, rc::Rc};
+
+/// Native implementations get the bound receiver (if any) and the call's arguments.
+pub type NativeFn = fn(Option<&ExprValue>, &[ExprValue]) -> NativeResult;
+
+pub type NativeResult = Result<ExprValue, NativeError>;
+
+/// Why a native function failed.
+#[derive(Debug)]
+pub enum NativeError {
+    /// A message, reported at the call site.
+    Message(String),
+    /// An error raised by Lox code the function ran, which keeps where it was raised
+    /// and any thrown value.
+    Runtime(RuntimeError),
+}
+
+impl From<String> for NativeError {
+    fn from(message: String) -> Self {
+        NativeError::Message(message)
+    }
+}
+
+/// A function implemented in Rust. Methods carry their receiver, bound at lookup.
+#[derive(Debug, Clone)]
+pub struct NativeFunction {
+    pub name: String,
+    pub arity: usize,
+    pub receiver: Option<ExprValue>,
+    pub function: NativeFn,
+}
+
+impl NativeFunction {
+    pub fn call(&self, arguments: &[ExprValue]) -> NativeResult {
+        (self.function)(self.receiver.as_ref(), arguments)
+    }
+}
+
+const GLOBAL_FUNCTIONS: &[(&str, usize, NativeFn)] = &[
+    ("type", 1, type_of),
+    ("fields", 1, fields),
+    ("methods", 1, methods),
+    ("iterable", 1, iterable),
+];
+
+/// The scope enclosing every script's and module's top level, holding the built-in
+/// functions. Being a scope of its own keeps them out of a module's exports, and lets
+/// scripts shadow them.
+pub fn globals() -> Rc<RefCell<Environment>> {
+    let mut environment = Environment::new();
+
+    for (name, arity, function) in GLOBAL_FUNCTIONS {
+        let function = ExprValue::NativeFunction(Rc::new(NativeFunction {
+            name: name.to_string(),
+            arity: *arity,
+            receiver: None,
+            function: *function,
+        }));
+        environment.define(name.to_string(), function);
+    }
+
+    Rc::new(RefCell::new(environment))
+}
+
+// a variant's type is the enum it belongs to
+fn type_of(_: Option<&ExprValue>, arguments: &[ExprValue]) -> NativeResult {
+    match &arguments[0] {
+        ExprValue::Variant(variant) => Ok(ExprValue::String(variant.owner.name.clone())),
+        argument => Ok(ExprValue::String(argument.type_name().to_string())),
+    }
+}
+
+/// The keys of a map's entries that aren't functions, in order.
+fn fields(_: Option<&ExprValue>, arguments: &[ExprValue]) -> NativeResult {
+    map_keys("fields", &arguments[0], |value| !is_function(value))
+}
+
+/// The keys of a map's entries that are functions, in order.
+fn methods(_: Option<&ExprValue>, arguments: &[ExprValue]) -> NativeResult {
+    map_keys("methods", &arguments[0], is_function)
+}
+
+/// Marks a map as taking part in the iteration protocol, returning the map.
+fn iterable(_: Option<&ExprValue>, arguments: &[ExprValue]) -> NativeResult {
+    match &arguments[0] {
+        ExprValue::Map(map) => {
+            map.borrow_mut().mark_iterable();
+            Ok(arguments[0].clone())
+        }
+        argument => Err(format!(
+            "Argument to 'iterable' must be a Map, got {}.",
+            argument.type_name()
+        )
+        .into()),
+    }
+}
+
+fn is_function(value: &ExprValue) -> bool {
+    matches!(value, ExprValue::Function(_) | ExprValue::NativeFunction(_))
+}
+
+fn map_keys(name: &str, argument: &ExprValue, keep: fn(&ExprValue) -> bool) -> NativeResult {
+    let ExprValue::Map(map) = argument else {
+        return Err(format!(
+            "Argument to '{name}' must be a Map, got {}.",
+            argument.type_name()
+        )
+        .into());
+    };
+
+    let keys = map
+        .borrow()
+        .iter()
+        .filter(|(_, value)| keep(value))
+        .map(|(key, _)| key.clone())
+        .collect();
+
+    Ok(ExprValue::List(Rc::new(RefCell::new(keys))))
+}
+
+const STRING_METHODS: &[(&str, usize, NativeFn)] = &[
+    ("upper", 0, upper),
+    ("lower", 0, lower),
+    ("trim", 0, trim),
+    ("split", 1, split),
+    ("contains", 1, contains),
+    ("replace", 2, replace),
+    ("startsWith", 1, starts_with),
+];
+
+/// Looks up `name` on a string: `length` is a plain value, everything else is a
+/// method bound to the string. Lengths count characters, not bytes.
+pub fn string_property(s: &str, name: &str) -> Option<ExprValue> {
+    if name == "length" {
+        return Some(ExprValue::Int(s.chars().count() as i64));
+    }
+
+    STRING_METHODS
+        .iter()
+        .find(|(method, _, _)| *method == name)
+        .map(|(name, arity, function)| {
+            ExprValue::NativeFunction(Rc::new(NativeFunction {
+                name: name.to_string(),
+                arity: *arity,
+                receiver: Some(ExprValue::String(s.to_string())),
+                function: *function,
+            }))
+        })
+}
+
+/// Looks up `name` on a generator: `next()` resumes it, returning the next yielded
+/// value, or `nil` once it has finished.
+pub fn generator_property(generator: &Rc<RefCell<Generator>>, name: &str) -> Option<ExprValue> {
+    match name {
+        "next" => Some(ExprValue::NativeFunction(Rc::new(NativeFunction {
+            name: "next".to_string(),
+            arity: 0,
+            receiver: Some(ExprValue::Generator(generator.clone())),
+            function: generator_next,
+        }))),
+        _ => None,
+    }
+}
+
+fn generator_next(receiver: Option<&ExprValue>, _: &[ExprValue]) -> NativeResult {
+    let Some(ExprValue::Generator(generator)) = receiver else {
+        unreachable!("generator methods are only bound to generators!");
+    };
+
+    let mut generator = generator
+        .try_borrow_mut()
+        .map_err(|_| "Generator is already running.".to_string())?;
+
+    match generator.resume() {
+        Ok(value) => Ok(value.unwrap_or(ExprValue::Nil)),
+        Err(err) => Err(NativeError::Runtime(err)),
+    }
+}
+
+const RANGE_METHODS: &[(&str, usize, NativeFn)] = &[
+    ("step", 1, step),
+    ("contains", 1, range_contains),
+    ("len", 0, len),
+];
+
+/// Looks up a method on a range, bound to it.
+pub fn range_property(range: Range, name: &str) -> Option<ExprValue> {
+    RANGE_METHODS
+        .iter()
+        .find(|(method, _, _)| *method == name)
+        .map(|(name, arity, function)| {
+            ExprValue::NativeFunction(Rc::new(NativeFunction {
+                name: name.to_string(),
+                arity: *arity,
+                receiver: Some(ExprValue::Range(range)),
+                function: *function,
+            }))
+        })
+}
+
+fn receiver_range(receiver: Option<&ExprValue>) -> Range {
+    match receiver {
+        Some(ExprValue::Range(range)) => *range,
+        _ => unreachable!("range methods are only bound to ranges!"),
+    }
+}
+
+fn step(receiver: Option<&ExprValue>, arguments: &[ExprValue]) -> NativeResult {
+    match arguments[0] {
+        ExprValue::Int(0) => Err("Range step can't be zero.".to_string().into()),
+        ExprValue::Int(step) => Ok(ExprValue::Range(Range {
+            step,
+            ..receiver_range(receiver)
+        })),
+        ref other => Err(format!(
+            "Argument to 'step' must be an Int, got {}.",
+            other.type_name()
+        )
+        .into()),
+    }
+}
+
+// anything that isn't a whole number is never in a range
+fn range_contains(receiver: Option<&ExprValue>, arguments: &[ExprValue]) -> NativeResult {
+    let range = receiver_range(receiver);
+
+    let contains = match arguments[0] {
+        ExprValue::Int(n) => range.contains(n),
+        ExprValue::Number(n)
+            if n.fract() == 0.0 && n >= i64::MIN as f64 && n < -(i64::MIN as f64) =>
+        {
+            range.contains(n as i64)
+        }
+        _ => false,
+    };
+
+    Ok(ExprValue::Bool(contains))
+}
+
+fn len(receiver: Option<&ExprValue>, _: &[ExprValue]) -> NativeResult {
+    i64::try_from(receiver_range(receiver).len())
+        .map(ExprValue::Int)
+        .map_err(|_| "Range is too long to measure.".to_string().into())
+}
+
+/// Looks up `name` on an enum: `values()` lists its variants, and each variant is a
+/// property. A variant with a payload is a function building it from the field values.
+pub fn enum_property(enumeration: &Rc<Enum>, name: &str) -> Option<ExprValue> {
+    if name == "values" {
+        return Some(ExprValue::NativeFunction(Rc::new(NativeFunction {
+            name: "values".to_string(),
+            arity: 0,
+            receiver: Some(ExprValue::Enum(enumeration.clone())),
+            function: values,
+        })));
+    }
+
+    let index = enumeration.variant(name)?;
+    let variant = Variant {
+        owner: enumeration.clone(),
+        index,
+        payload: Vec::new(),
+    };
+
+    match variant.fields().len() {
+        0 => Some(ExprValue::Variant(Rc::new(variant))),
+        arity => Some(ExprValue::NativeFunction(Rc::new(NativeFunction {
+            name: format!("{}.{name}", enumeration.name),
+            arity,
+            receiver: Some(ExprValue::Variant(Rc::new(variant))),
+            function: build_variant,
+        }))),
+    }
+}
+
+fn values(receiver: Option<&ExprValue>, _: &[ExprValue]) -> NativeResult {
+    let Some(ExprValue::Enum(enumeration)) = receiver else {
+        unreachable!("'values' is only bound to enums!");
+    };
+
+    let mut variants = Vec::new();
+    for (index, (name, fields)) in enumeration.variants.iter().enumerate() {
+        if !fields.is_empty() {
+            return Err(format!(
+                "Can't list the values of '{}': variant '{name}' carries a payload.",
+                enumeration.name
+            )
+            .into());
+        }
+
+        variants.push(ExprValue::Variant(Rc::new(Variant {
+            owner: enumeration.clone(),
+            index,
+            payload: Vec::new(),
+        })));
+    }
+
+    Ok(ExprValue::List(Rc::new(RefCell::new(variants))))
+}
+
+// the receiver is the variant without its payload
+fn build_variant(receiver: Option<&ExprValue>, arguments: &[ExprValue]) -> NativeResult {
+    let Some(ExprValue::Variant(variant)) = receiver else {
+        unreachable!("variant constructors are only bound to variants!");
+    };
+
+    Ok(ExprValue::Variant(Rc::new(Variant {
+        owner: variant.owner.clone(),
+        index: variant.index,
+        payload: arguments.to_vec(),
+    })))
+}
+
+fn receiver_str(receiver: Option<&ExprValue>) -> &str {
+    match receiver {
+        Some(ExprValue::String(s)) => s,
+        _ => unreachable!("string methods are only bound to strings!"),
+    }
+}
+
+fn string_argument<'a>(method: &str, argument: &'a ExprValue) -> Result<&'a str, String> {
+    match argument {
+        ExprValue::String(s) => Ok(s),
+        other => Err(format!(
+            "Argument to '{method}' must be a String, got {}.",
+            other.type_name()
+        )),
+    }
+}
+
+fn upper(receiver: Option<&ExprValue>, _: &[ExprValue]) -> NativeResult {
+    Ok(ExprValue::String(receiver_str(receiver).to_uppercase()))
+}
+
+fn lower(receiver: Option<&ExprValue>, _: &[ExprValue]) -> NativeResult {
+    Ok(ExprValue::String(receiver_str(receiver).to_lowercase()))
+}
+
+fn trim(receiver: Option<&ExprValue>, _: &[ExprValue]) -> NativeResult {
+    Ok(ExprValue::String(receiver_str(receiver).trim().to_string()))
+}
+
+fn split(receiver: Option<&ExprValue>, arguments: &[ExprValue]) -> NativeResult {
+    let (s, separator) = (
+        receiver_str(receiver),
+        string_argument("split", &arguments[0])?,
+    );
+
+    // an empty separator splits into characters
+    let parts = if separator.is_empty() {
+        s.chars()
+            .map(|c| ExprValue::String(c.to_string()))
+            .collect()
+    } else {
+        s.split(separator)
+            .map(|part| ExprValue::String(part.to_string()))
+            .collect()
+    };
+
+    Ok(ExprValue::List(Rc::new(RefCell::new(parts))))
+}
+
+fn contains(receiver: Option<&ExprValue>, arguments: &[ExprValue]) -> NativeResult {
+    let needle = string_argument("contains", &arguments[0])?;
+    Ok(ExprValue::Bool(receiver_str(receiver).contains(needle)))
+}
+
+fn replace(receiver: Option<&ExprValue>, arguments: &[ExprValue]) -> NativeResult {
+    let from = string_argument("replace", &arguments[0])?;
+    let to = string_argument("replace", &arguments[1])?;
+    Ok(ExprValue::String(receiver_str(receiver).replace(from, to)))
+}
+
+fn starts_with(receiver: Option<&ExprValue>, arguments: &[ExprValue]) -> NativeResult {
+    let prefix = string_argument("startsWith", &arguments[0])?;
+    Ok(ExprValue::Bool(receiver_str(receiver).starts_with(prefix)))
+}
@@ -1,4 +1,9 @@
-use crate::{Expr, Stmt, Token, TokenLiteral, TokenType};
+use crate::{EnumVariant, Expr, MatchArm, Param, Pattern, Stmt, Token, TokenLiteral, TokenType};
+use crate::{Type, TypeAnnotation};
+use std::{
+    collections::{BTreeSet, HashMap},
+    rc::Rc,
+};
 
 #[derive(Debug, thiserror::Error)]
 #[error("{0}")]
@@ -7,6 +12,16 @@ pub struct ParseError(String);
 pub struct Parser {
     tokens: Vec<Token>,
     current: usize,
+    // labels of the loops enclosing the current statement, innermost last
+    loops: Vec<Option<String>>,
+    // names declared in each enclosing block (true for `const`), innermost last
+    scopes: Vec<HashMap<String, bool>>,
+    // the function bodies enclosing the current statement, innermost last, each
+    // noting whether it has a `yield` so far
+    functions: Vec<bool>,
+    // position of the `=>` ending the match arm guard being parsed, which can't be
+    // taken for an arrow function's
+    arm_arrow: Option<usize>,
 }
 
 pub type ParseResult = Result<Expr, ParseError>;
@@ -14,7 +29,14 @@ pub type ParseStmtResult = Result<Stmt, ParseError>;
 
 impl Parser {
     pub fn new(tokens: Vec<Token>) -> Self {
-        Self { tokens, current: 0 }
+        Self {
+            tokens,
+            current: 0,
+            loops: Vec::new(),
+            scopes: vec![HashMap::new()],
+            functions: Vec::new(),
+            arm_arrow: None,
+        }
     }
 
     pub fn parse(&mut self) -> Result<Vec<Stmt>, ParseError> {
@@ -30,22 +52,36 @@ impl Parser {
     fn declaration(&mut self) -> ParseStmtResult {
         if self.match_types(&[TokenType::VAR]) {
             self.var_declaration()
+        } else if self.match_types(&[TokenType::CONST]) {
+            self.const_declaration()
+        } else if self.check(&TokenType::FUN) && self.check_next(&TokenType::IDENTIFIER) {
+            self.advance();
+            self.function_declaration()
+        } else if self.match_types(&[TokenType::ENUM]) {
+            self.enum_declaration()
+        } else if self.match_types(&[TokenType::IMPORT]) {
+            self.import_declaration()
+        } else if self.match_types(&[TokenType::FROM]) {
+            self.import_from_declaration()
         } else {
             self.statement()
         }
-        .map_err(|err| {
-            self.synchronize();
-            err
-        })
+        .inspect_err(|_| self.synchronize())
     }
 
     fn var_declaration(&mut self) -> ParseStmtResult {
-        let name = self.consume(&TokenType::IDENTIFIER, "Expect variable name.")?;
-
-        let initializer = if self.match_types(&[TokenType::ASSIGN]) {
-            self.expression()?
-        } else {
-            Expr::Nil
+        let pattern = self.declaration_pattern("Expect variable name.")?;
+        let annotation = self.type_annotation()?;
+
+        // only a plain name can go without an initializer
+        let initializer = match (self.match_types(&[TokenType::ASSIGN]), &pattern) {
+            (true, _) => self.expression()?,
+            (false, Pattern::Binding(_)) => Expr::Nil,
+            (false, _) => {
+                return Err(ParseError(
+                    "Expect '=' after destructuring pattern.".to_string(),
+                ))
+            }
         };
 
         let _ = self.consume(
@@ -53,19 +89,669 @@ impl Parser {
             "Expect ';' after variable declaration.",
         )?;
 
-        Ok(Stmt::Var(name, initializer))
+        for name in pattern.bindings() {
+            self.declare(name, false)?;
+        }
+        Ok(Stmt::Var(pattern, annotation, initializer))
+    }
+
+    // a name, or a list or map pattern to destructure the initializer with
+    fn declaration_pattern(&mut self, message: &str) -> Result<Pattern, ParseError> {
+        if !self.check(&TokenType::LEFTBRACKET) && !self.check(&TokenType::LEFTBRACE) {
+            let name = self.consume(&TokenType::IDENTIFIER, message)?;
+            return Ok(Pattern::Binding(name));
+        }
+
+        let line = self.peek().line;
+        let pattern = self.single_pattern()?;
+
+        match pattern.is_destructurable() {
+            true => Ok(pattern),
+            false => Err(ParseError(format!(
+                "Only names, '_', lists and maps can be destructured [line {line}]"
+            ))),
+        }
+    }
+
+    // `fun name(params) { ... }` binds a function value to `name`, like a `var`
+    fn function_declaration(&mut self) -> ParseStmtResult {
+        let keyword = self.previous().clone();
+        let name = self.consume(&TokenType::IDENTIFIER, "Expect function name.")?;
+        self.consume(&TokenType::LEFTPAREN, "Expect '(' after function name.")?;
+        let params = self.parameters()?;
+        let return_type = self.type_annotation()?;
+        self.consume(&TokenType::LEFTBRACE, "Expect '{' before function body.")?;
+
+        // declared before the body so the function can call itself
+        self.declare(&name, false)?;
+        let (body, generator) = self.function_body(&params, |parser| parser.block_statements())?;
+
+        let function = Expr::Function {
+            keyword,
+            name: Some(name.clone()),
+            params,
+            return_type,
+            body: Rc::new(body),
+            generator,
+        };
+        Ok(Stmt::Var(Pattern::Binding(name), None, function))
+    }
+
+    fn const_declaration(&mut self) -> ParseStmtResult {
+        let pattern = self.declaration_pattern("Expect constant name.")?;
+        let annotation = self.type_annotation()?;
+        self.consume(&TokenType::ASSIGN, "Expect '=' after constant name.")?;
+        let initializer = self.expression()?;
+
+        let _ = self.consume(
+            &TokenType::SEMICOLON,
+            "Expect ';' after constant declaration.",
+        )?;
+
+        for name in pattern.bindings() {
+            self.declare(name, true)?;
+        }
+        Ok(Stmt::Const(pattern, annotation, initializer))
+    }
+
+    // an optional `: Type` after a declared name, parameter list or parameter
+    fn type_annotation(&mut self) -> Result<Option<TypeAnnotation>, ParseError> {
+        if !self.match_types(&[TokenType::COLON]) {
+            return Ok(None);
+        }
+
+        // any other name has to be an enum, which is only known once the program runs
+        // or is checked
+        let name = self.consume(&TokenType::IDENTIFIER, "Expect type name after ':'.")?;
+        let ty = Type::from_name(&name.lexeme).unwrap_or(Type::Variant);
+        Ok(Some(TypeAnnotation { name, ty }))
+    }
+
+    // `enum Name { A, B(field, ...), ... }`, with an optional trailing comma
+    fn enum_declaration(&mut self) -> ParseStmtResult {
+        let name = self.consume(&TokenType::IDENTIFIER, "Expect enum name.")?;
+        self.consume(&TokenType::LEFTBRACE, "Expect '{' after enum name.")?;
+
+        let mut variants: Vec<EnumVariant> = Vec::new();
+        while !self.check(&TokenType::RIGHTBRACE) {
+            let variant = self.consume(&TokenType::IDENTIFIER, "Expect variant name.")?;
+            if variants
+                .iter()
+                .any(|other| other.name.lexeme == variant.lexeme)
+            {
+                return Err(ParseError(format!(
+                    "Duplicate variant '{}' in enum '{}' [line {}]",
+                    variant.lexeme, name.lexeme, variant.line
+                )));
+            }
+
+            let mut fields: Vec<Token> = Vec::new();
+            if self.match_types(&[TokenType::LEFTPAREN]) {
+                while !self.check(&TokenType::RIGHTPAREN) {
+                    let field = self.consume(&TokenType::IDENTIFIER, "Expect field name.")?;
+                    if fields.iter().any(|other| other.lexeme == field.lexeme) {
+                        return Err(ParseError(format!(
+                            "Duplicate field '{}' in variant '{}' [line {}]",
+                            field.lexeme, variant.lexeme, field.line
+                        )));
+                    }
+                    fields.push(field);
+
+                    if !self.match_types(&[TokenType::COMMA]) {
+                        break;
+                    }
+                }
+                self.consume(&TokenType::RIGHTPAREN, "Expect ')' after variant fields.")?;
+            }
+
+            variants.push(EnumVariant {
+                name: variant,
+                fields,
+            });
+
+            if !self.match_types(&[TokenType::COMMA]) {
+                break;
+            }
+        }
+
+        self.consume(&TokenType::RIGHTBRACE, "Expect '}' after enum variants.")?;
+
+        self.declare(&name, false)?;
+        Ok(Stmt::Enum { name, variants })
+    }
+
+    // records a declaration in the current block, rejecting redeclared constants
+    fn declare(&mut self, name: &Token, is_const: bool) -> Result<(), ParseError> {
+        let scope = self.scopes.last_mut().expect("parser always has a scope");
+
+        if scope.get(&name.lexeme) == Some(&true) {
+            return Err(ParseError(format!(
+                "Cannot redeclare constant '{}' [line {}]",
+                name.lexeme, name.line
+            )));
+        }
+
+        scope.insert(name.lexeme.clone(), is_const);
+        Ok(())
+    }
+
+    // assignments to a name that resolves to a visible `const` are rejected up front;
+    // names this parser can't see (globals declared later, imports) are checked at runtime
+    fn check_not_const(&self, name: &Token) -> Result<(), ParseError> {
+        let is_const = self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(&name.lexeme));
+
+        match is_const {
+            Some(true) => Err(ParseError(format!(
+                "Cannot assign to constant '{}' [line {}]",
+                name.lexeme, name.line
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    fn import_declaration(&mut self) -> ParseStmtResult {
+        let keyword = self.previous().clone();
+        let path = self.module_path()?;
+
+        // `as` is only special here, so it stays usable as a name elsewhere
+        match self.match_types(&[TokenType::IDENTIFIER]) {
+            true if self.previous().lexeme == "as" => (),
+            _ => return Err(ParseError("Expect 'as' after module path.".to_string())),
+        }
+
+        let alias = self.consume(&TokenType::IDENTIFIER, "Expect module name after 'as'.")?;
+        self.consume(&TokenType::SEMICOLON, "Expect ';' after import.")?;
+
+        self.declare(&alias, false)?;
+        Ok(Stmt::Import {
+            keyword,
+            path,
+            alias,
+        })
+    }
+
+    fn import_from_declaration(&mut self) -> ParseStmtResult {
+        let keyword = self.previous().clone();
+        let path = self.module_path()?;
+        self.consume(&TokenType::IMPORT, "Expect 'import' after module path.")?;
+
+        let mut names = vec![self.consume(&TokenType::IDENTIFIER, "Expect name to import.")?];
+        while self.match_types(&[TokenType::COMMA]) {
+            names.push(self.consume(&TokenType::IDENTIFIER, "Expect name to import.")?);
+        }
+
+        self.consume(&TokenType::SEMICOLON, "Expect ';' after import.")?;
+
+        for name in &names {
+            self.declare(name, false)?;
+        }
+
+        Ok(Stmt::FromImport {
+            keyword,
+            path,
+            names,
+        })
+    }
+
+    fn module_path(&mut self) -> Result<String, ParseError> {
+        self.consume(&TokenType::STRING, "Expect module path string.")?;
+
+        match &self.previous().literal {
+            Some(TokenLiteral::String(path)) => Ok(path.clone()),
+            _ => Err(ParseError("Expect module path string.".to_string())),
+        }
     }
 
     fn statement(&mut self) -> ParseStmtResult {
         if self.match_types(&[TokenType::PRINT]) {
             self.print_statement()
+        } else if self.pattern_assignment_ahead() {
+            self.expression_statement()
         } else if self.match_types(&[TokenType::LEFTBRACE]) {
             self.block()
+        } else if self.match_types(&[TokenType::THROW]) {
+            self.throw_statement()
+        } else if self.match_types(&[TokenType::RETURN]) {
+            self.return_statement()
+        } else if self.match_types(&[TokenType::YIELD]) {
+            self.yield_statement()
+        } else if self.match_types(&[TokenType::FOR]) {
+            self.for_statement(None)
+        } else if self.match_types(&[TokenType::WHILE]) {
+            self.while_statement(None)
+        } else if self.match_types(&[TokenType::MATCH]) {
+            self.match_statement()
+        } else if self.match_types(&[TokenType::TRY]) {
+            self.try_statement()
+        } else if self.match_types(&[TokenType::BREAK, TokenType::CONTINUE]) {
+            self.loop_control_statement()
+        } else if self.check(&TokenType::IDENTIFIER) && self.check_next(&TokenType::COLON) {
+            self.labeled_statement()
         } else {
             self.expression_statement()
         }
     }
 
+    fn throw_statement(&mut self) -> ParseStmtResult {
+        let keyword = self.previous().clone();
+        let value = self.expression()?;
+        self.consume(&TokenType::SEMICOLON, "Expect ';' after thrown value.")?;
+
+        Ok(Stmt::Throw(keyword, value))
+    }
+
+    fn return_statement(&mut self) -> ParseStmtResult {
+        let keyword = self.previous().clone();
+
+        if self.functions.is_empty() {
+            return Err(ParseError(format!(
+                "Can't return from top-level code [line {}]",
+                keyword.line
+            )));
+        }
+
+        let value = match self.check(&TokenType::SEMICOLON) {
+            true => None,
+            false => Some(self.expression()?),
+        };
+        self.consume(&TokenType::SEMICOLON, "Expect ';' after return value.")?;
+
+        Ok(Stmt::Return(keyword, value))
+    }
+
+    fn yield_statement(&mut self) -> ParseStmtResult {
+        let keyword = self.previous().clone();
+
+        match self.functions.last_mut() {
+            Some(generator) => *generator = true,
+            None => {
+                return Err(ParseError(format!(
+                    "Can't yield outside of a function [line {}]",
+                    keyword.line
+                )))
+            }
+        }
+
+        let value = match self.check(&TokenType::SEMICOLON) {
+            true => None,
+            false => Some(self.expression()?),
+        };
+        self.consume(&TokenType::SEMICOLON, "Expect ';' after yielded value.")?;
+
+        Ok(Stmt::Yield(keyword, value))
+    }
+
+    // `for (x in iterable) body`, with the `for` already consumed
+    fn for_statement(&mut self, label: Option<Token>) -> ParseStmtResult {
+        let keyword = self.previous().clone();
+        self.consume(&TokenType::LEFTPAREN, "Expect '(' after 'for'.")?;
+        let variable = self.consume(&TokenType::IDENTIFIER, "Expect loop variable name.")?;
+        self.consume(&TokenType::IN, "Expect 'in' after loop variable.")?;
+        let iterable = self.expression()?;
+        self.consume(&TokenType::RIGHTPAREN, "Expect ')' after loop iterable.")?;
+
+        self.loops
+            .push(label.as_ref().map(|label| label.lexeme.clone()));
+        self.scopes
+            .push(HashMap::from([(variable.lexeme.clone(), false)]));
+        let body = self.statement();
+        self.scopes.pop();
+        self.loops.pop();
+
+        // a block body runs directly in the scope holding the loop variable
+        let body = match body? {
+            Stmt::Block(statements) => statements,
+            body => Rc::new(vec![body]),
+        };
+
+        Ok(Stmt::ForIn {
+            keyword,
+            label,
+            variable,
+            iterable,
+            body,
+        })
+    }
+
+    // `while (condition) body`, with the `while` already consumed
+    fn while_statement(&mut self, label: Option<Token>) -> ParseStmtResult {
+        self.consume(&TokenType::LEFTPAREN, "Expect '(' after 'while'.")?;
+        let condition = self.expression()?;
+        self.consume(&TokenType::RIGHTPAREN, "Expect ')' after condition.")?;
+
+        self.loops
+            .push(label.as_ref().map(|label| label.lexeme.clone()));
+        let body = self.statement();
+        self.loops.pop();
+
+        let body = match body? {
+            Stmt::Block(statements) => statements,
+            body => Rc::new(vec![body]),
+        };
+
+        Ok(Stmt::While {
+            label,
+            condition,
+            body,
+        })
+    }
+
+    // `match (value) { pattern if guard => body, ... }`; commas between arms are optional
+    fn match_statement(&mut self) -> ParseStmtResult {
+        let keyword = self.previous().clone();
+        self.consume(&TokenType::LEFTPAREN, "Expect '(' after 'match'.")?;
+        let value_line = self.peek().line;
+        let value = self.expression()?;
+        self.consume(&TokenType::RIGHTPAREN, "Expect ')' after match value.")?;
+        self.consume(&TokenType::LEFTBRACE, "Expect '{' before match arms.")?;
+
+        let mut arms = Vec::new();
+        while !self.check(&TokenType::RIGHTBRACE) && !self.is_at_end() {
+            let pattern = self.pattern()?;
+
+            // the pattern's bindings are visible to the guard and the body
+            self.scopes.push(
+                pattern
+                    .bindings()
+                    .into_iter()
+                    .map(|name| (name.lexeme.clone(), false))
+                    .collect(),
+            );
+            let arm = self.match_arm(pattern);
+            self.scopes.pop();
+
+            arms.push(arm?);
+            self.match_types(&[TokenType::COMMA]);
+        }
+
+        self.consume(&TokenType::RIGHTBRACE, "Expect '}' after match arms.")?;
+
+        Ok(Stmt::Match {
+            keyword,
+            value,
+            value_line,
+            arms,
+        })
+    }
+
+    // the rest of a match arm after its pattern
+    fn match_arm(&mut self, pattern: Pattern) -> Result<MatchArm, ParseError> {
+        let guard = match self.match_types(&[TokenType::IF]) {
+            true => {
+                let position = self.arm_arrow_position();
+                let enclosing = std::mem::replace(&mut self.arm_arrow, position);
+                let guard = self.expression();
+                self.arm_arrow = enclosing;
+                Some(guard?)
+            }
+            false => None,
+        };
+
+        self.consume(&TokenType::ARROW, "Expect '=>' after pattern.")?;
+
+        let body = match self.statement()? {
+            Stmt::Block(statements) => statements,
+            body => Rc::new(vec![body]),
+        };
+
+        Ok(MatchArm {
+            pattern,
+            guard,
+            body,
+        })
+    }
+
+    // the first `=>` outside any brackets from here, which ends a match arm's guard
+    fn arm_arrow_position(&self) -> Option<usize> {
+        let mut depth = 0;
+
+        for (i, token) in self.tokens.iter().enumerate().skip(self.current) {
+            match token.token_type {
+                TokenType::LEFTPAREN | TokenType::LEFTBRACKET | TokenType::LEFTBRACE => depth += 1,
+                TokenType::RIGHTPAREN | TokenType::RIGHTBRACKET | TokenType::RIGHTBRACE => {
+                    depth -= 1
+                }
+                TokenType::ARROW if depth == 0 => return Some(i),
+                TokenType::EOF => return None,
+                _ => (),
+            }
+        }
+
+        None
+    }
+
+    fn pattern(&mut self) -> Result<Pattern, ParseError> {
+        let mut alternatives = vec![self.single_pattern()?];
+
+        // an arm's body sees the same names whichever alternative matched
+        while self.match_types(&[TokenType::PIPE]) {
+            let pipe = self.previous().clone();
+            let alternative = self.single_pattern()?;
+
+            if bound_names(&alternative) != bound_names(&alternatives[0]) {
+                return Err(ParseError(format!(
+                    "All alternatives must bind the same variables [line {}]",
+                    pipe.line
+                )));
+            }
+            alternatives.push(alternative);
+        }
+
+        match alternatives.len() {
+            1 => Ok(alternatives.remove(0)),
+            _ => Ok(Pattern::Alternatives(alternatives)),
+        }
+    }
+
+    fn single_pattern(&mut self) -> Result<Pattern, ParseError> {
+        if self.match_types(&[TokenType::IDENTIFIER]) {
+            let name = self.previous().clone();
+
+            return match name.lexeme.as_str() {
+                "_" => Ok(Pattern::Wildcard),
+                _ => Ok(Pattern::Binding(name)),
+            };
+        }
+
+        if self.match_types(&[TokenType::LEFTBRACKET]) {
+            let bracket = self.previous().clone();
+            let (mut elements, mut rest) = (Vec::new(), None);
+
+            while !self.check(&TokenType::RIGHTBRACKET) {
+                // a rest pattern has to come last, which the closing ']' checks
+                if self.match_types(&[TokenType::DOTDOTDOT]) {
+                    rest = Some(self.consume(&TokenType::IDENTIFIER, "Expect name after '...'.")?);
+                    break;
+                }
+
+                elements.push(self.pattern()?);
+
+                if !self.match_types(&[TokenType::COMMA]) {
+                    break;
+                }
+            }
+
+            self.consume(&TokenType::RIGHTBRACKET, "Expect ']' after list pattern.")?;
+            return Ok(Pattern::List {
+                bracket,
+                elements,
+                rest,
+            });
+        }
+
+        if self.match_types(&[TokenType::LEFTBRACE]) {
+            let (brace, mut entries) = (self.previous().clone(), Vec::new());
+
+            while !self.check(&TokenType::RIGHTBRACE) {
+                let key = match self.match_types(&[TokenType::IDENTIFIER, TokenType::STRING]) {
+                    true => self.previous().clone(),
+                    false => {
+                        return Err(ParseError(format!(
+                            "Expect key in map pattern [line {}]",
+                            self.peek().line
+                        )))
+                    }
+                };
+
+                let pattern = if self.match_types(&[TokenType::COLON]) {
+                    self.pattern()?
+                } else if key.token_type == TokenType::IDENTIFIER {
+                    Pattern::Binding(key.clone())
+                } else {
+                    return Err(ParseError(format!(
+                        "Expect ':' after string key in map pattern [line {}]",
+                        key.line
+                    )));
+                };
+
+                let key = match key.literal {
+                    Some(TokenLiteral::String(key)) => key,
+                    _ => key.lexeme,
+                };
+                entries.push((key, pattern));
+
+                if !self.match_types(&[TokenType::COMMA]) {
+                    break;
+                }
+            }
+
+            self.consume(&TokenType::RIGHTBRACE, "Expect '}' after map pattern.")?;
+            return Ok(Pattern::Map { brace, entries });
+        }
+
+        // literals, where numbers may be negative
+        if self.check(&TokenType::MINUS) && self.check_next(&TokenType::NUMBER) {
+            let operator = self.advance().clone();
+            let number = self.primary()?;
+
+            return Ok(Pattern::Literal(Expr::Unary {
+                operator,
+                right: Box::new(number),
+            }));
+        }
+
+        if [
+            TokenType::NUMBER,
+            TokenType::STRING,
+            TokenType::TRUE,
+            TokenType::FALSE,
+            TokenType::NIL,
+        ]
+        .iter()
+        .any(|token_type| self.check(token_type))
+        {
+            return Ok(Pattern::Literal(self.primary()?));
+        }
+
+        Err(ParseError(format!(
+            "Expect pattern [line {}]",
+            self.peek().line
+        )))
+    }
+
+    // `try { } catch (e) { } finally { }`, where either handler may be left out
+    fn try_statement(&mut self) -> ParseStmtResult {
+        let keyword = self.previous().clone();
+        self.consume(&TokenType::LEFTBRACE, "Expect '{' after 'try'.")?;
+        let body = Rc::new(self.block_statements()?);
+
+        let catch = match self.match_types(&[TokenType::CATCH]) {
+            true => {
+                self.consume(&TokenType::LEFTPAREN, "Expect '(' after 'catch'.")?;
+                let name = self.consume(&TokenType::IDENTIFIER, "Expect error variable name.")?;
+                self.consume(&TokenType::RIGHTPAREN, "Expect ')' after error variable.")?;
+                self.consume(&TokenType::LEFTBRACE, "Expect '{' before catch body.")?;
+
+                // the error variable lives in its own scope around the handler
+                self.scopes
+                    .push(HashMap::from([(name.lexeme.clone(), false)]));
+                let handler = self.block_statements();
+                self.scopes.pop();
+
+                Some((name, Rc::new(handler?)))
+            }
+            false => None,
+        };
+
+        let finally = match self.match_types(&[TokenType::FINALLY]) {
+            true => {
+                self.consume(&TokenType::LEFTBRACE, "Expect '{' after 'finally'.")?;
+                Some(Rc::new(self.block_statements()?))
+            }
+            false => None,
+        };
+
+        if catch.is_none() && finally.is_none() {
+            return Err(ParseError(format!(
+                "Expect 'catch' or 'finally' after try block [line {}]",
+                keyword.line
+            )));
+        }
+
+        Ok(Stmt::Try {
+            body,
+            catch,
+            finally,
+        })
+    }
+
+    // `break;`, `continue;` or with a label naming an enclosing loop: `break outer;`
+    fn loop_control_statement(&mut self) -> ParseStmtResult {
+        let keyword = self.previous().clone();
+
+        let label = match self.match_types(&[TokenType::IDENTIFIER]) {
+            true => Some(self.previous().clone()),
+            false => None,
+        };
+
+        if self.loops.is_empty() {
+            return Err(ParseError(format!(
+                "Can't use '{}' outside of a loop [line {}]",
+                keyword.lexeme, keyword.line
+            )));
+        }
+
+        if let Some(label) = &label {
+            if !self.loops.contains(&Some(label.lexeme.clone())) {
+                return Err(ParseError(format!(
+                    "Undefined loop label '{}' [line {}]",
+                    label.lexeme, label.line
+                )));
+            }
+        }
+
+        self.consume(
+            &TokenType::SEMICOLON,
+            &format!("Expect ';' after '{}'.", keyword.lexeme),
+        )?;
+
+        match keyword.token_type {
+            TokenType::BREAK => Ok(Stmt::Break(keyword, label)),
+            _ => Ok(Stmt::Continue(keyword, label)),
+        }
+    }
+
+    // `label: <loop>`; the label is only visible to `break`/`continue` inside that loop
+    fn labeled_statement(&mut self) -> ParseStmtResult {
+        let label = self.advance().clone();
+        self.advance(); // ':'
+
+        if self.match_types(&[TokenType::FOR]) {
+            self.for_statement(Some(label))
+        } else if self.match_types(&[TokenType::WHILE]) {
+            self.while_statement(Some(label))
+        } else {
+            Err(ParseError(format!(
+                "Expect loop after label '{}' [line {}]",
+                label.lexeme, label.line
+            )))
+        }
+    }
+
     fn print_statement(&mut self) -> ParseStmtResult {
         let val = self.expression()?;
         // let _ = self.consume(&TokenType::SEMICOLON, "Expect ';' after value.")?;
@@ -84,7 +770,13 @@ impl Parser {
     }
 
     fn block(&mut self) -> Result<Stmt, ParseError> {
+        Ok(Stmt::Block(Rc::new(self.block_statements()?)))
+    }
+
+    // the statements of a block whose '{' has already been consumed
+    fn block_statements(&mut self) -> Result<Vec<Stmt>, ParseError> {
         let mut statements = Vec::new();
+        self.scopes.push(HashMap::new());
 
         while !self.check(&TokenType::RIGHTBRACE) && !self.is_at_end() {
             statements.push(self.declaration()?);
@@ -92,7 +784,8 @@ impl Parser {
 
         let _ = self.consume(&TokenType::RIGHTBRACE, "Expect '}' after block.")?;
 
-        Ok(Stmt::Block(statements))
+        self.scopes.pop();
+        Ok(statements)
     }
 
     fn expression(&mut self) -> ParseResult {
@@ -100,14 +793,34 @@ impl Parser {
     }
 
     fn assignment(&mut self) -> ParseResult {
-        let expr = self.equality()?;
+        if self.pattern_assignment_ahead() {
+            return self.pattern_assignment();
+        }
+
+        let expr = self.conditional()?;
 
         if self.match_types(&[TokenType::ASSIGN]) {
             let equals = self.previous().clone();
             let value = self.assignment()?;
 
-            if let Expr::Variable(name) = expr {
-                return Ok(Expr::Assign(name, Box::new(value)));
+            match expr {
+                Expr::Variable(name) => {
+                    self.check_not_const(&name)?;
+                    return Ok(Expr::Assign(name, Box::new(value)));
+                }
+                Expr::Index {
+                    object,
+                    bracket,
+                    index,
+                } => {
+                    return Ok(Expr::SetIndex {
+                        object,
+                        bracket,
+                        index,
+                        value: Box::new(value),
+                    })
+                }
+                _ => (),
             }
 
             return Err(ParseError(format!(
@@ -116,15 +829,75 @@ impl Parser {
             )));
         }
 
+        if self.match_types(&[
+            TokenType::PLUSEQUAL,
+            TokenType::MINUSEQUAL,
+            TokenType::STAREQUAL,
+            TokenType::SLASHEQUAL,
+        ]) {
+            let operator = self.previous().clone();
+            let value = self.assignment()?;
+            self.check_assignable(&expr, &operator)?;
+
+            return Ok(Expr::CompoundAssign {
+                target: Box::new(expr),
+                operator,
+                value: Box::new(value),
+            });
+        }
+
+        Ok(expr)
+    }
+
+    fn conditional(&mut self) -> ParseResult {
+        let condition = self.coalesce()?;
+
+        if self.match_types(&[TokenType::QUESTION]) {
+            let question = self.previous().clone();
+            let then_branch = self.expression()?;
+            self.consume(
+                &TokenType::COLON,
+                &format!(
+                    "Expect ':' after then branch of conditional expression [line {}]",
+                    question.line
+                ),
+            )?;
+            // right-associative: `a ? b : c ? d : e` nests in the else branch
+            let else_branch = self.conditional()?;
+
+            return Ok(Expr::Conditional {
+                condition: Box::new(condition),
+                then_branch: Box::new(then_branch),
+                else_branch: Box::new(else_branch),
+            });
+        }
+
+        Ok(condition)
+    }
+
+    fn coalesce(&mut self) -> ParseResult {
+        let mut expr = self.equality()?;
+
+        while self.match_types(&[TokenType::QUESTIONQUESTION]) {
+            let operator = self.previous().clone();
+            let right = self.equality()?;
+
+            expr = Expr::Logical {
+                operator,
+                right: Box::new(right),
+                left: Box::new(expr),
+            };
+        }
+
         Ok(expr)
     }
 
     fn equality(&mut self) -> ParseResult {
-        let mut expr = self.comparison()?;
+        let mut expr = self.bit_or()?;
 
         while self.match_types(&[TokenType::NOTEQUAL, TokenType::EQUAL]) {
             let operator = self.previous().clone();
-            let right = self.comparison()?;
+            let right = self.bit_or()?;
 
             expr = Expr::Binary {
                 left: Box::new(expr),
@@ -136,16 +909,123 @@ impl Parser {
         Ok(expr)
     }
 
+    // bitwise operators sit between equality and comparison: `|` < `^` < `&`
+    fn bit_or(&mut self) -> ParseResult {
+        let mut expr = self.bit_xor()?;
+
+        while self.match_types(&[TokenType::PIPE]) {
+            let operator = self.previous().clone();
+            let right = self.bit_xor()?;
+
+            expr = Expr::Binary {
+                operator,
+                right: Box::new(right),
+                left: Box::new(expr),
+            };
+        }
+
+        Ok(expr)
+    }
+
+    fn bit_xor(&mut self) -> ParseResult {
+        let mut expr = self.bit_and()?;
+
+        while self.match_types(&[TokenType::CARET]) {
+            let operator = self.previous().clone();
+            let right = self.bit_and()?;
+
+            expr = Expr::Binary {
+                operator,
+                right: Box::new(right),
+                left: Box::new(expr),
+            };
+        }
+
+        Ok(expr)
+    }
+
+    fn bit_and(&mut self) -> ParseResult {
+        let mut expr = self.comparison()?;
+
+        while self.match_types(&[TokenType::AMPERSAND]) {
+            let operator = self.previous().clone();
+            let right = self.comparison()?;
+
+            expr = Expr::Binary {
+                operator,
+                right: Box::new(right),
+                left: Box::new(expr),
+            };
+        }
+
+        Ok(expr)
+    }
+
     fn comparison(&mut self) -> ParseResult {
-        let mut expr = self.term()?;
+        let mut expr = self.range()?;
 
         while self.match_types(&[
             TokenType::GREATER,
             TokenType::GREATEREQUAL,
             TokenType::LESS,
             TokenType::LESSEQUAL,
+            TokenType::IS,
         ]) {
             let operator = self.previous().clone();
+
+            if operator.token_type == TokenType::IS {
+                let type_name =
+                    self.consume(&TokenType::IDENTIFIER, "Expect type name after 'is'.")?;
+                expr = Expr::Is {
+                    value: Box::new(expr),
+                    keyword: operator,
+                    type_name,
+                };
+                continue;
+            }
+
+            let right = self.range()?;
+
+            expr = Expr::Binary {
+                operator,
+                right: Box::new(right),
+                left: Box::new(expr),
+            };
+        }
+
+        Ok(expr)
+    }
+
+    // ranges don't chain: `a..b..c` is an error
+    fn range(&mut self) -> ParseResult {
+        let start = self.shift()?;
+
+        if !self.match_types(&[TokenType::DOTDOT, TokenType::DOTDOTEQUAL]) {
+            return Ok(start);
+        }
+
+        let operator = self.previous().clone();
+        let end = self.shift()?;
+
+        if self.check(&TokenType::DOTDOT) || self.check(&TokenType::DOTDOTEQUAL) {
+            return Err(ParseError(format!(
+                "Range operators can't be chained [line {}]",
+                self.peek().line
+            )));
+        }
+
+        Ok(Expr::Range {
+            start: Box::new(start),
+            operator,
+            end: Box::new(end),
+        })
+    }
+
+    fn shift(&mut self) -> ParseResult {
+        let mut expr = self.term()?;
+
+        while self.match_types(&[TokenType::LESSLESS, TokenType::GREATERGREATER]) {
+            let operator = self.previous().clone();
             let right = self.term()?;
 
             expr = Expr::Binary {
@@ -178,7 +1058,12 @@ impl Parser {
     fn factor(&mut self) -> ParseResult {
         let mut expr = self.unary()?;
 
-        while self.match_types(&[TokenType::SLASH, TokenType::STAR]) {
+        while self.match_types(&[
+            TokenType::SLASH,
+            TokenType::STAR,
+            TokenType::PERCENT,
+            TokenType::TILDESLASH,
+        ]) {
             let operator = self.previous().clone();
             let right = self.unary()?;
 
@@ -193,7 +1078,7 @@ impl Parser {
     }
 
     fn unary(&mut self) -> ParseResult {
-        if self.match_types(&[TokenType::BANG, TokenType::MINUS]) {
+        if self.match_types(&[TokenType::BANG, TokenType::MINUS, TokenType::TILDE]) {
             let (operator, right) = (self.previous().clone(), self.unary()?);
 
             return Ok(Expr::Unary {
@@ -202,7 +1087,148 @@ impl Parser {
             });
         }
 
-        self.primary()
+        if self.match_types(&[TokenType::PLUSPLUS, TokenType::MINUSMINUS]) {
+            let (operator, target) = (self.previous().clone(), self.unary()?);
+            self.check_assignable(&target, &operator)?;
+
+            return Ok(Expr::Increment {
+                target: Box::new(target),
+                operator,
+                postfix: false,
+            });
+        }
+
+        self.power()
+    }
+
+    fn power(&mut self) -> ParseResult {
+        let expr = self.postfix()?;
+
+        if self.match_types(&[TokenType::STARSTAR]) {
+            // right-associative, and binds tighter than a unary minus on its left
+            let (operator, right) = (self.previous().clone(), self.unary()?);
+
+            return Ok(Expr::Binary {
+                operator,
+                right: Box::new(right),
+                left: Box::new(expr),
+            });
+        }
+
+        Ok(expr)
+    }
+
+    fn postfix(&mut self) -> ParseResult {
+        let expr = self.call()?;
+
+        if self.match_types(&[TokenType::PLUSPLUS, TokenType::MINUSMINUS]) {
+            let operator = self.previous().clone();
+            self.check_assignable(&expr, &operator)?;
+
+            return Ok(Expr::Increment {
+                target: Box::new(expr),
+                operator,
+                postfix: true,
+            });
+        }
+
+        Ok(expr)
+    }
+
+    fn call(&mut self) -> ParseResult {
+        let mut expr = self.primary()?;
+
+        loop {
+            if self.match_types(&[TokenType::LEFTPAREN]) {
+                expr = self.finish_call(expr, false)?;
+            } else if self.match_types(&[TokenType::LEFTBRACKET]) {
+                expr = self.finish_index(expr)?;
+            } else if self.match_types(&[TokenType::DOT]) {
+                let name =
+                    self.consume(&TokenType::IDENTIFIER, "Expect property name after '.'.")?;
+                expr = Expr::Get {
+                    object: Box::new(expr),
+                    name,
+                    optional: false,
+                };
+            } else if self.match_types(&[TokenType::QUESTIONDOT]) {
+                // `?.name` or `?.(args)`
+                if self.match_types(&[TokenType::LEFTPAREN]) {
+                    expr = self.finish_call(expr, true)?;
+                } else {
+                    let name =
+                        self.consume(&TokenType::IDENTIFIER, "Expect property name after '?.'.")?;
+                    expr = Expr::Get {
+                        object: Box::new(expr),
+                        name,
+                        optional: true,
+                    };
+                }
+            } else {
+                break;
+            }
+        }
+
+        Ok(expr)
+    }
+
+    // `[index]`, or a `[start:end]` slice where either bound may be omitted
+    fn finish_index(&mut self, object: Expr) -> ParseResult {
+        let bracket = self.previous().clone();
+
+        let start = match self.check(&TokenType::COLON) {
+            true => None,
+            false => Some(Box::new(self.expression()?)),
+        };
+
+        let start = match (start, self.match_types(&[TokenType::COLON])) {
+            (Some(index), false) => {
+                self.consume(&TokenType::RIGHTBRACKET, "Expect ']' after index.")?;
+
+                return Ok(Expr::Index {
+                    object: Box::new(object),
+                    bracket,
+                    index,
+                });
+            }
+            (start, _) => start,
+        };
+
+        let end = match self.check(&TokenType::RIGHTBRACKET) {
+            true => None,
+            false => Some(Box::new(self.expression()?)),
+        };
+        self.consume(&TokenType::RIGHTBRACKET, "Expect ']' after slice.")?;
+
+        Ok(Expr::Slice {
+            object: Box::new(object),
+            bracket,
+            start,
+            end,
+        })
+    }
+
+    fn finish_call(&mut self, callee: Expr, optional: bool) -> ParseResult {
+        let mut arguments = Vec::new();
+
+        if !self.check(&TokenType::RIGHTPAREN) {
+            loop {
+                arguments.push(self.expression()?);
+
+                if !self.match_types(&[TokenType::COMMA]) {
+                    break;
+                }
+            }
+        }
+
+        let paren = self.consume(&TokenType::RIGHTPAREN, "Expect ')' after arguments.")?;
+
+        Ok(Expr::Call {
+            callee: Box::new(callee),
+            paren,
+            arguments,
+            optional,
+        })
     }
 
     fn primary(&mut self) -> ParseResult {
@@ -215,8 +1241,10 @@ impl Parser {
         }
 
         if self.match_types(&[TokenType::NUMBER]) {
-            if let Some(TokenLiteral::Number(num)) = &self.previous().literal {
-                return Ok(Expr::Number(*num));
+            match &self.previous().literal {
+                Some(TokenLiteral::Int(num)) => return Ok(Expr::Int(*num)),
+                Some(TokenLiteral::Number(num)) => return Ok(Expr::Number(*num)),
+                _ => (),
             }
         }
 
@@ -230,7 +1258,50 @@ impl Parser {
             return Ok(Expr::Variable(self.previous().clone()));
         }
 
+        if self.match_types(&[TokenType::LEFTBRACKET]) {
+            let mut elements = Vec::new();
+
+            // a trailing comma is allowed
+            while !self.check(&TokenType::RIGHTBRACKET) {
+                elements.push(self.expression()?);
+
+                if !self.match_types(&[TokenType::COMMA]) {
+                    break;
+                }
+            }
+
+            self.consume(&TokenType::RIGHTBRACKET, "Expect ']' after list elements.")?;
+            return Ok(Expr::List(elements));
+        }
+
+        // in expression position braces are a map literal; `statement` has already
+        // claimed a leading `{` as a block
+        if self.match_types(&[TokenType::LEFTBRACE]) {
+            let (brace, mut entries) = (self.previous().clone(), Vec::new());
+
+            while !self.check(&TokenType::RIGHTBRACE) {
+                let key = self.expression()?;
+                self.consume(&TokenType::COLON, "Expect ':' after map key.")?;
+                entries.push((key, self.expression()?));
+
+                if !self.match_types(&[TokenType::COMMA]) {
+                    break;
+                }
+            }
+
+            self.consume(&TokenType::RIGHTBRACE, "Expect '}' after map entries.")?;
+            return Ok(Expr::Map { brace, entries });
+        }
+
+        if self.match_types(&[TokenType::FUN]) {
+            return self.function_expression();
+        }
+
         if self.match_types(&[TokenType::LEFTPAREN]) {
+            if self.arrow_ahead() {
+                return self.arrow_function();
+            }
+
             let expr = self.expression()?;
             self.consume(&TokenType::RIGHTPAREN, "Expect ')' after expression.")?;
             return Ok(Expr::Grouping(Box::new(expr)));
@@ -242,6 +1313,231 @@ impl Parser {
         )))
     }
 
+    // `fun (a, b) { ... }`, with the `fun` already consumed
+    fn function_expression(&mut self) -> ParseResult {
+        let keyword = self.previous().clone();
+        self.consume(&TokenType::LEFTPAREN, "Expect '(' after 'fun'.")?;
+        let params = self.parameters()?;
+        let return_type = self.type_annotation()?;
+        self.consume(&TokenType::LEFTBRACE, "Expect '{' before function body.")?;
+
+        let (body, generator) = self.function_body(&params, |parser| parser.block_statements())?;
+
+        Ok(Expr::Function {
+            keyword,
+            name: None,
+            params,
+            return_type,
+            body: Rc::new(body),
+            generator,
+        })
+    }
+
+    // `(a, b) => expr`, with the `(` already consumed
+    fn arrow_function(&mut self) -> ParseResult {
+        let params = self.parameters()?;
+        let return_type = self.type_annotation()?;
+        let arrow = self.consume(&TokenType::ARROW, "Expect '=>' after parameters.")?;
+
+        let (body, _) = self.function_body(&params, |parser| {
+            let value = parser.expression()?;
+            Ok(vec![Stmt::Return(arrow.clone(), Some(value))])
+        })?;
+
+        Ok(Expr::Function {
+            keyword: arrow,
+            name: None,
+            params,
+            return_type,
+            body: Rc::new(body),
+            generator: false,
+        })
+    }
+
+    // the parameter list after its `(`, up to and including the `)`; once a parameter
+    // has a default every later one needs one too, and a rest parameter comes last
+    fn parameters(&mut self) -> Result<Vec<Param>, ParseError> {
+        let mut params: Vec<Param> = Vec::new();
+
+        while !self.check(&TokenType::RIGHTPAREN) {
+            if self.match_types(&[TokenType::DOTDOTDOT]) {
+                let name = self.consume(&TokenType::IDENTIFIER, "Expect rest parameter name.")?;
+                let annotation = self.type_annotation()?;
+
+                if annotation
+                    .as_ref()
+                    .is_some_and(|annotation| !Type::List.accepts(annotation.ty))
+                {
+                    return Err(ParseError(format!(
+                        "Rest parameter '{}' is always a List [line {}]",
+                        name.lexeme, name.line
+                    )));
+                }
+
+                if !self.check(&TokenType::RIGHTPAREN) {
+                    return Err(ParseError(format!(
+                        "Rest parameter '{}' must be the last parameter [line {}]",
+                        name.lexeme, name.line
+                    )));
+                }
+
+                params.push(Param {
+                    name,
+                    default: None,
+                    rest: true,
+                    annotation,
+                });
+                break;
+            }
+
+            let name = self.consume(&TokenType::IDENTIFIER, "Expect parameter name.")?;
+            let annotation = self.type_annotation()?;
+            let default = match self.match_types(&[TokenType::ASSIGN]) {
+                true => Some(self.conditional()?),
+                false => None,
+            };
+
+            if default.is_none() && params.iter().any(|param| param.default.is_some()) {
+                return Err(ParseError(format!(
+                    "Parameter '{}' needs a default value since an earlier one has one [line {}]",
+                    name.lexeme, name.line
+                )));
+            }
+
+            params.push(Param {
+                name,
+                default,
+                rest: false,
+                annotation,
+            });
+
+            if !self.match_types(&[TokenType::COMMA]) {
+                break;
+            }
+        }
+
+        self.consume(&TokenType::RIGHTPAREN, "Expect ')' after parameters.")?;
+        Ok(params)
+    }
+
+    /// Parses a function body with `parse_body` in a fresh scope holding the
+    /// parameters, also reporting whether the body yields. Loops outside the function
+    /// can't be targeted from inside it.
+    fn function_body<F>(
+        &mut self,
+        params: &[Param],
+        parse_body: F,
+    ) -> Result<(Vec<Stmt>, bool), ParseError>
+    where
+        F: FnOnce(&mut Self) -> Result<Vec<Stmt>, ParseError>,
+    {
+        let loops = std::mem::take(&mut self.loops);
+        self.scopes.push(
+            params
+                .iter()
+                .map(|param| (param.name.lexeme.clone(), false))
+                .collect(),
+        );
+        self.functions.push(false);
+
+        let body = parse_body(self);
+
+        let generator = self.functions.pop().unwrap_or_default();
+        self.scopes.pop();
+        self.loops = loops;
+
+        Ok((body?, generator))
+    }
+
+    // after a `(`: whether the parentheses hold an arrow function's parameters, i.e.
+    // the matching `)` is followed by `=>`, or by a return type and then `=>`
+    fn arrow_ahead(&self) -> bool {
+        let mut depth = 0;
+
+        for (i, token) in self.tokens.iter().enumerate().skip(self.current) {
+            match token.token_type {
+                TokenType::LEFTPAREN | TokenType::LEFTBRACKET | TokenType::LEFTBRACE => depth += 1,
+                TokenType::RIGHTPAREN if depth == 0 => {
+                    let is_at = |offset: usize, token_type: TokenType| {
+                        self.tokens
+                            .get(i + offset)
+                            .is_some_and(|token| token.token_type == token_type)
+                    };
+                    let arrow = match is_at(1, TokenType::COLON) {
+                        true if is_at(2, TokenType::IDENTIFIER) => i + 3,
+                        _ => i + 1,
+                    };
+
+                    return is_at(arrow - i, TokenType::ARROW) && self.arm_arrow != Some(arrow);
+                }
+                TokenType::RIGHTPAREN | TokenType::RIGHTBRACKET | TokenType::RIGHTBRACE => {
+                    depth -= 1
+                }
+                TokenType::EOF => return false,
+                _ => (),
+            }
+        }
+
+        false
+    }
+
+    // `[a, b] = value` or `{x, "y": b} = value`, through the same patterns a
+    // destructuring `var` declares with
+    fn pattern_assignment(&mut self) -> ParseResult {
+        let pattern = self.declaration_pattern("Expect pattern.")?;
+        for name in pattern.bindings() {
+            self.check_not_const(name)?;
+        }
+
+        let equals = self.consume(&TokenType::ASSIGN, "Expect '=' after pattern.")?;
+        let value = self.assignment()?;
+
+        Ok(Expr::AssignPattern {
+            pattern: Box::new(pattern),
+            equals,
+            value: Box::new(value),
+        })
+    }
+
+    // at a `[` or `{`: whether the matching bracket is followed by `=`, making them a
+    // pattern to assign through rather than a list, a map or a block
+    fn pattern_assignment_ahead(&self) -> bool {
+        if !self.check(&TokenType::LEFTBRACKET) && !self.check(&TokenType::LEFTBRACE) {
+            return false;
+        }
+
+        let mut depth = 0;
+        for (i, token) in self.tokens.iter().enumerate().skip(self.current) {
+            match token.token_type {
+                TokenType::LEFTPAREN | TokenType::LEFTBRACKET | TokenType::LEFTBRACE => depth += 1,
+                TokenType::RIGHTPAREN | TokenType::RIGHTBRACKET | TokenType::RIGHTBRACE => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return self
+                            .tokens
+                            .get(i + 1)
+                            .is_some_and(|token| token.token_type == TokenType::ASSIGN);
+                    }
+                }
+                TokenType::EOF => return false,
+                _ => (),
+            }
+        }
+
+        false
+    }
+
+    fn check_assignable(&self, target: &Expr, operator: &Token) -> Result<(), ParseError> {
+        match target {
+            Expr::Variable(name) => self.check_not_const(name),
+            Expr::Index { .. } => Ok(()),
+            _ => Err(ParseError(format!(
+                "Invalid assignment target [line {}]",
+                operator.line
+            ))),
+        }
+    }
+
     fn match_types(&mut self, token_types: &[TokenType]) -> bool {
         for token_type in token_types.iter() {
             if self.check(token_type) {
@@ -255,7 +1551,7 @@ impl Parser {
 
     fn consume(&mut self, token_type: &TokenType, message: &str) -> Result<Token, ParseError> {
         if self.check(token_type) {
-            return Ok(self.advance().clone());
+            Ok(self.advance().clone())
         } else {
             Err(ParseError(message.to_string()))
         }
@@ -269,6 +1565,13 @@ impl Parser {
         &self.peek().token_type == token_type
     }
 
+    fn check_next(&self, token_type: &TokenType) -> bool {
+        match self.tokens.get(self.current + 1) {
+            Some(token) => &token.token_type == token_type,
+            None => false,
+        }
+    }
+
     fn advance(&mut self) -> &Token {
         if !self.is_at_end() {
             self.current += 1;
@@ -301,11 +1604,21 @@ impl Parser {
                 TokenType::CLASS
                 | TokenType::FUN
                 | TokenType::VAR
+                | TokenType::CONST
+                | TokenType::ENUM
                 | TokenType::FOR
                 | TokenType::IF
                 | TokenType::WHILE
                 | TokenType::PRINT
-                | TokenType::RETURN => return,
+                | TokenType::RETURN
+                | TokenType::YIELD
+                | TokenType::MATCH
+                | TokenType::BREAK
+                | TokenType::CONTINUE
+                | TokenType::THROW
+                | TokenType::TRY
+                | TokenType::IMPORT
+                | TokenType::FROM => return,
                 _ => (),
             }
 
@@ -313,3 +1626,12 @@ impl Parser {
         }
     }
 }
+
+// the names a pattern binds, as a set
+fn bound_names(pattern: &Pattern) -> BTreeSet<&str> {
+    pattern
+        .bindings()
+        .into_iter()
+        .map(|name| name.lexeme.as_str())
+        .collect()
+}
@@ -0,0 +1,102 @@
+use crate::{Expr, Token};
+use std::fmt::{Display, Formatter};
+
+/// The left-hand side of a `match` arm, or of a destructuring declaration or
+/// assignment, where only names, `_`, list and map patterns are allowed.
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    /// `_` matches anything without binding it.
+    Wildcard,
+    /// A name matches anything and binds it.
+    Binding(Token),
+    /// A number, string, boolean or `nil`, matched with `==`.
+    Literal(Expr),
+    /// `[a, b]` matches lists of exactly that length; `[a, ...rest]` of at least that
+    /// length, binding the remaining elements to `rest` as a list.
+    List {
+        bracket: Token,
+        elements: Vec<Pattern>,
+        rest: Option<Token>,
+    },
+    /// `{x, "y": p}` matches maps that have every listed key (others are ignored).
+    /// A bare name is short for `"name": name`.
+    Map {
+        brace: Token,
+        entries: Vec<(String, Pattern)>,
+    },
+    /// `a | b` matches when any of the alternatives does, trying them in order.
+    Alternatives(Vec<Pattern>),
+}
+
+impl Pattern {
+    /// Whether this pattern can be destructured: a literal or alternatives could fail
+    /// to match, which only a `match` can deal with.
+    pub fn is_destructurable(&self) -> bool {
+        match self {
+            Pattern::Wildcard | Pattern::Binding(_) => true,
+            Pattern::Literal(_) | Pattern::Alternatives(_) => false,
+            Pattern::List { elements, .. } => elements.iter().all(Pattern::is_destructurable),
+            Pattern::Map { entries, .. } => entries
+                .iter()
+                .all(|(_, pattern)| pattern.is_destructurable()),
+        }
+    }
+
+    /// The names this pattern binds, in order.
+    pub fn bindings(&self) -> Vec<&Token> {
+        match self {
+            Pattern::Wildcard | Pattern::Literal(_) => Vec::new(),
+            Pattern::Binding(name) => vec![name],
+            Pattern::List { elements, rest, .. } => elements
+                .iter()
+                .flat_map(|element| element.bindings())
+                .chain(rest)
+                .collect(),
+            Pattern::Map { entries, .. } => entries
+                .iter()
+                .flat_map(|(_, pattern)| pattern.bindings())
+                .collect(),
+            // every alternative binds the same names
+            Pattern::Alternatives(alternatives) => alternatives[0].bindings(),
+        }
+    }
+}
+
+impl Display for Pattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Pattern::Wildcard => write!(f, "_"),
+            Pattern::Binding(name) => write!(f, "{}", name.lexeme),
+            Pattern::Literal(literal) => write!(f, "{literal}"),
+            Pattern::List { elements, rest, .. } => {
+                let mut parts = elements
+                    .iter()
+                    .map(|element| element.to_string())
+                    .collect::<Vec<String>>();
+                if let Some(rest) = rest {
+                    parts.push(format!("...{}", rest.lexeme));
+                }
+
+                write!(f, "[{}]", parts.join(", "))
+            }
+            Pattern::Map { entries, .. } => {
+                let entries = entries
+                    .iter()
+                    .map(|(key, pattern)| format!("{key}: {pattern}"))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+
+                write!(f, "{{{entries}}}")
+            }
+            Pattern::Alternatives(alternatives) => {
+                let alternatives = alternatives
+                    .iter()
+                    .map(|alternative| alternative.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" | ");
+
+                write!(f, "{alternatives}")
+            }
+        }
+    }
+}
@@ -0,0 +1,56 @@
+/// A lazy sequence of integers from `start` towards `end`, `step` apart. `end` is
+/// only part of the sequence for inclusive (`..=`) ranges that land on it exactly;
+/// a negative step counts down.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Range {
+    pub start: i64,
+    pub end: i64,
+    pub inclusive: bool,
+    pub step: i64,
+}
+
+impl Range {
+    pub fn new(start: i64, end: i64, inclusive: bool) -> Self {
+        Range {
+            start,
+            end,
+            inclusive,
+            step: 1,
+        }
+    }
+
+    pub fn len(&self) -> u128 {
+        // i128 so that the distance between any two i64s fits
+        let (start, end, step) = (self.start as i128, self.end as i128, self.step as i128);
+        let distance = if step > 0 { end - start } else { start - end };
+
+        let span = match self.inclusive {
+            true if distance >= 0 => distance,
+            false if distance > 0 => distance - 1,
+            _ => return 0,
+        };
+
+        (span / step.abs() + 1) as u128
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The `index`th value of the sequence, if it has that many.
+    pub fn get(&self, index: u128) -> Option<i64> {
+        if index >= self.len() {
+            return None;
+        }
+
+        Some((self.start as i128 + index as i128 * self.step as i128) as i64)
+    }
+
+    pub fn contains(&self, n: i64) -> bool {
+        let offset = n as i128 - self.start as i128;
+
+        offset % self.step as i128 == 0
+            && (offset / self.step as i128) >= 0
+            && ((offset / self.step as i128) as u128) < self.len()
+    }
+}
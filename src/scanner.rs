@@ -22,21 +22,35 @@ impl Scanner {
             line: 1,
             keywords: HashMap::from([
                 ("and", TokenType::AND),
+                ("break", TokenType::BREAK),
+                ("catch", TokenType::CATCH),
                 ("class", TokenType::CLASS),
+                ("const", TokenType::CONST),
+                ("continue", TokenType::CONTINUE),
                 ("else", TokenType::ELSE),
+                ("enum", TokenType::ENUM),
                 ("false", TokenType::FALSE),
+                ("finally", TokenType::FINALLY),
                 ("for", TokenType::FOR),
+                ("from", TokenType::FROM),
                 ("fun", TokenType::FUN),
                 ("if", TokenType::IF),
+                ("import", TokenType::IMPORT),
+                ("in", TokenType::IN),
+                ("is", TokenType::IS),
+                ("match", TokenType::MATCH),
                 ("nil", TokenType::NIL),
                 ("or", TokenType::OR),
                 ("print", TokenType::PRINT),
                 ("return", TokenType::RETURN),
                 ("super", TokenType::SUPER),
                 ("this", TokenType::THIS),
+                ("throw", TokenType::THROW),
                 ("true", TokenType::TRUE),
+                ("try", TokenType::TRY),
                 ("var", TokenType::VAR),
                 ("while", TokenType::WHILE),
+                ("yield", TokenType::YIELD),
             ]),
         }
     }
@@ -60,39 +74,111 @@ impl Scanner {
             ')' => self.add_token(TokenType::RIGHTPAREN, None),
             '{' => self.add_token(TokenType::LEFTBRACE, None),
             '}' => self.add_token(TokenType::RIGHTBRACE, None),
+            '[' => self.add_token(TokenType::LEFTBRACKET, None),
+            ']' => self.add_token(TokenType::RIGHTBRACKET, None),
             ',' => self.add_token(TokenType::COMMA, None),
-            '.' => self.add_token(TokenType::DOT, None),
-            '-' => self.add_token(TokenType::MINUS, None),
-            '+' => self.add_token(TokenType::PLUS, None),
+            '.' => {
+                if !self.operator_match('.') {
+                    self.add_token(TokenType::DOT, None)
+                } else if self.operator_match('.') {
+                    self.add_token(TokenType::DOTDOTDOT, None)
+                } else if self.operator_match('=') {
+                    self.add_token(TokenType::DOTDOTEQUAL, None)
+                } else {
+                    self.add_token(TokenType::DOTDOT, None)
+                }
+            }
+            '-' => {
+                if self.operator_match('-') {
+                    self.add_token(TokenType::MINUSMINUS, None)
+                } else if self.operator_match('=') {
+                    self.add_token(TokenType::MINUSEQUAL, None)
+                } else {
+                    self.add_token(TokenType::MINUS, None)
+                }
+            }
+            '+' => {
+                if self.operator_match('+') {
+                    self.add_token(TokenType::PLUSPLUS, None)
+                } else if self.operator_match('=') {
+                    self.add_token(TokenType::PLUSEQUAL, None)
+                } else {
+                    self.add_token(TokenType::PLUS, None)
+                }
+            }
             ';' => self.add_token(TokenType::SEMICOLON, None),
-            '*' => self.add_token(TokenType::STAR, None),
+            '?' => {
+                if self.operator_match('?') {
+                    self.add_token(TokenType::QUESTIONQUESTION, None)
+                } else if self.operator_match('.') {
+                    self.add_token(TokenType::QUESTIONDOT, None)
+                } else {
+                    self.add_token(TokenType::QUESTION, None)
+                }
+            }
+            ':' => self.add_token(TokenType::COLON, None),
+            '%' => self.add_token(TokenType::PERCENT, None),
+            '*' => {
+                if self.operator_match('*') {
+                    self.add_token(TokenType::STARSTAR, None)
+                } else if self.operator_match('=') {
+                    self.add_token(TokenType::STAREQUAL, None)
+                } else {
+                    self.add_token(TokenType::STAR, None)
+                }
+            }
 
             '!' => match self.operator_match('=') {
                 true => self.add_token(TokenType::NOTEQUAL, None),
                 false => self.add_token(TokenType::BANG, None),
             },
-            '=' => match self.operator_match('=') {
-                true => self.add_token(TokenType::EQUAL, None),
-                false => self.add_token(TokenType::ASSIGN, None),
-            },
-            '<' => match self.operator_match('=') {
-                true => self.add_token(TokenType::LESSEQUAL, None),
-                false => self.add_token(TokenType::LESS, None),
-            },
-            '>' => match self.operator_match('=') {
-                true => self.add_token(TokenType::GREATEREQUAL, None),
-                false => self.add_token(TokenType::GREATER, None),
-            },
+            '=' => {
+                if self.operator_match('=') {
+                    self.add_token(TokenType::EQUAL, None)
+                } else if self.operator_match('>') {
+                    self.add_token(TokenType::ARROW, None)
+                } else {
+                    self.add_token(TokenType::ASSIGN, None)
+                }
+            }
+            '<' => {
+                if self.operator_match('=') {
+                    self.add_token(TokenType::LESSEQUAL, None)
+                } else if self.operator_match('<') {
+                    self.add_token(TokenType::LESSLESS, None)
+                } else {
+                    self.add_token(TokenType::LESS, None)
+                }
+            }
+            '>' => {
+                if self.operator_match('=') {
+                    self.add_token(TokenType::GREATEREQUAL, None)
+                } else if self.operator_match('>') {
+                    self.add_token(TokenType::GREATERGREATER, None)
+                } else {
+                    self.add_token(TokenType::GREATER, None)
+                }
+            }
+            '&' => self.add_token(TokenType::AMPERSAND, None),
+            '|' => self.add_token(TokenType::PIPE, None),
+            '^' => self.add_token(TokenType::CARET, None),
             '/' => {
                 // advance through comment in code
                 if self.operator_match('/') {
                     while self.peek() != '\n' && !self.is_at_end() {
                         self.advance();
                     }
+                } else if self.operator_match('=') {
+                    self.add_token(TokenType::SLASHEQUAL, None)
                 } else {
                     self.add_token(TokenType::SLASH, None)
                 }
             }
+            // floor division is spelled `~/` since `//` already starts a comment
+            '~' => match self.operator_match('/') {
+                true => self.add_token(TokenType::TILDESLASH, None),
+                false => self.add_token(TokenType::TILDE, None),
+            },
             ' ' | '\r' | '\t' => (),
             '\n' => self.line += 1,
 
@@ -133,32 +219,130 @@ impl Scanner {
     }
 
     fn number(&mut self) {
-        while self.peek().is_ascii_digit() {
-            self.advance();
+        if self.source[self.start] == '0' {
+            match self.peek() {
+                'x' | 'X' => return self.radix_number(16, "hexadecimal"),
+                'b' | 'B' => return self.radix_number(2, "binary"),
+                'o' | 'O' => return self.radix_number(8, "octal"),
+                _ => (),
+            }
+        }
+
+        if !self.digits(10) {
+            return;
         }
 
         if self.peek() == '.' && self.peek_next().is_ascii_digit() {
             self.advance();
 
-            while self.peek().is_ascii_digit() {
+            if !self.digits(10) {
+                return;
+            }
+        }
+
+        if matches!(self.peek(), 'e' | 'E') {
+            self.advance();
+
+            if matches!(self.peek(), '+' | '-') {
                 self.advance();
             }
+
+            if !self.peek().is_ascii_digit() {
+                self.skip_literal_tail();
+                return self.number_error("expected digits in exponent");
+            }
+
+            if !self.digits(10) {
+                return;
+            }
         }
 
-        let num_str = &self.source[self.start..self.current]
+        // digit separators are only for readability
+        let num_str = self.source[self.start..self.current]
             .iter()
+            .filter(|c| **c != '_')
             .collect::<String>();
 
-        if let Ok(num) = num_str.parse::<f64>() {
+        // an integer too big for an Int is still a number, just not an exact one
+        let int = Some(&num_str)
+            .filter(|num_str| !num_str.contains(['.', 'e', 'E']))
+            .and_then(|num_str| num_str.parse::<i64>().ok());
+
+        if let Some(num) = int {
+            self.add_token(TokenType::NUMBER, Some(TokenLiteral::Int(num)));
+        } else if let Ok(num) = num_str.parse::<f64>() {
             self.add_token(TokenType::NUMBER, Some(TokenLiteral::Number(num)));
         } else {
-            self.errors.push(format!(
-                "[line {}] Error: Invalid number literal: {}",
-                self.line, num_str
-            ))
+            self.number_error("not a valid number")
+        }
+    }
+
+    fn radix_number(&mut self, radix: u32, name: &str) {
+        let prefix = self.advance();
+
+        if !self.peek().is_digit(radix) {
+            self.skip_literal_tail();
+            return self.number_error(&format!("expected {name} digits after '0{prefix}'"));
+        }
+
+        if !self.digits(radix) {
+            return;
+        }
+
+        if self.peek().is_alphanumeric() {
+            let invalid = self.peek();
+            self.skip_literal_tail();
+            return self.number_error(&format!("invalid digit '{invalid}' in {name} literal"));
+        }
+
+        let digits = self.source[self.start + 2..self.current]
+            .iter()
+            .filter(|c| **c != '_')
+            .collect::<String>();
+
+        match i64::from_str_radix(&digits, radix) {
+            Ok(num) => self.add_token(TokenType::NUMBER, Some(TokenLiteral::Int(num))),
+            Err(_) => self.number_error(&format!("{name} literal is out of range")),
         }
     }
 
+    /// Consumes a run of digits in `radix`, allowing single `_` separators between digits.
+    /// Returns false (after reporting the error) when a separator is misplaced.
+    fn digits(&mut self, radix: u32) -> bool {
+        while self.peek().is_digit(radix) || self.peek() == '_' {
+            if self.peek() == '_'
+                && !(self.source[self.current - 1].is_digit(radix)
+                    && self.peek_next().is_digit(radix))
+            {
+                self.skip_literal_tail();
+                self.number_error("digit separator '_' must be between digits");
+                return false;
+            }
+
+            self.advance();
+        }
+
+        true
+    }
+
+    // swallow the rest of a malformed literal so it isn't rescanned as an identifier
+    fn skip_literal_tail(&mut self) {
+        while self.peek().is_alphanumeric() || self.peek() == '_' {
+            self.advance();
+        }
+    }
+
+    fn number_error(&mut self, reason: &str) {
+        let num_str = self.source[self.start..self.current]
+            .iter()
+            .collect::<String>();
+
+        self.errors.push(format!(
+            "[line {}] Error: Invalid number literal: {} ({})",
+            self.line, num_str, reason
+        ))
+    }
+
     fn string(&mut self) {
         while self.peek() != '"' && !self.is_at_end() {
             if self.peek() == '\n' {
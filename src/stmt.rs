@@ -1,18 +1,100 @@
-use crate::{Expr, Token};
-use std::fmt::{Display, Formatter};
+use crate::{Expr, Pattern, Token, TypeAnnotation};
+use std::{
+    fmt::{Display, Formatter},
+    rc::Rc,
+};
 
 #[derive(Debug, Clone)]
 pub enum Stmt {
     Expression(Expr),
     Print(Expr),
-    Var(Token, Expr),
-    Block(Vec<Stmt>),
+    /// `var name: Type = value;`, or a destructuring `var [a, b] = value;`
+    Var(Pattern, Option<TypeAnnotation>, Expr),
+    Const(Pattern, Option<TypeAnnotation>, Expr),
+    /// Statement lists are shared so a suspended generator can keep its place in them.
+    Block(Rc<Vec<Stmt>>),
+    /// The keyword, plus the label of the loop to exit (innermost when `None`).
+    Break(Token, Option<Token>),
+    Continue(Token, Option<Token>),
+    /// `import "path" as alias;`
+    Import {
+        keyword: Token,
+        path: String,
+        alias: Token,
+    },
+    /// `from "path" import a, b;`
+    FromImport {
+        keyword: Token,
+        path: String,
+        names: Vec<Token>,
+    },
+    Throw(Token, Expr),
+    Return(Token, Option<Expr>),
+    Try {
+        body: Rc<Vec<Stmt>>,
+        catch: Option<(Token, Rc<Vec<Stmt>>)>,
+        finally: Option<Rc<Vec<Stmt>>>,
+    },
+    /// `for (variable in iterable) body`, optionally labeled. The body runs in a
+    /// fresh scope per iteration that holds the loop variable.
+    ForIn {
+        keyword: Token,
+        label: Option<Token>,
+        variable: Token,
+        iterable: Expr,
+        body: Rc<Vec<Stmt>>,
+    },
+    /// `while (condition) body`, optionally labeled. The body runs in a fresh scope
+    /// per iteration.
+    While {
+        label: Option<Token>,
+        condition: Expr,
+        body: Rc<Vec<Stmt>>,
+    },
+    /// Only valid in a function body, which makes that function a generator.
+    Yield(Token, Option<Expr>),
+    /// `enum Name { A, B(field) }`, binding `Name` to the enum its variants are reached through.
+    Enum {
+        name: Token,
+        variants: Vec<EnumVariant>,
+    },
+    /// `match (value) { pattern => body, ... }`; `value_line` is where the value was
+    /// written, for reporting a value no arm matched.
+    Match {
+        keyword: Token,
+        value: Expr,
+        value_line: usize,
+        arms: Vec<MatchArm>,
+    },
+}
+
+/// A variant of an `enum` declaration, with the names of the fields its payload has.
+#[derive(Debug, Clone)]
+pub struct EnumVariant {
+    pub name: Token,
+    pub fields: Vec<Token>,
+}
+
+/// `pattern if guard => body`. The body runs in a scope holding the pattern's bindings,
+/// which the guard can also see.
+#[derive(Debug, Clone)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub guard: Option<Expr>,
+    pub body: Rc<Vec<Stmt>>,
 }
 
 impl Display for Stmt {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            Stmt::Var(tok, expr) => write!(f, "{} = {}", tok.lexeme, expr),
+            Stmt::Var(pattern, annotation, expr) => match annotation {
+                Some(annotation) => write!(f, "{}: {} = {}", pattern, annotation, expr),
+                None => write!(f, "{} = {}", pattern, expr),
+            },
+            Stmt::Const(pattern, annotation, expr) => match annotation {
+                Some(annotation) => write!(f, "const {}: {} = {}", pattern, annotation, expr),
+                None => write!(f, "const {} = {}", pattern, expr),
+            },
             Stmt::Print(expr) | Stmt::Expression(expr) => write!(f, "{}", expr),
             Stmt::Block(statements) => {
                 let stmts = statements
@@ -23,6 +105,104 @@ impl Display for Stmt {
 
                 write!(f, "{}", stmts)
             }
+            Stmt::Import { path, alias, .. } => write!(f, "import {} as {}", path, alias.lexeme),
+            Stmt::FromImport { path, names, .. } => {
+                let names = names
+                    .iter()
+                    .map(|name| name.lexeme.as_str())
+                    .collect::<Vec<&str>>()
+                    .join(", ");
+
+                write!(f, "from {} import {}", path, names)
+            }
+            Stmt::Throw(_, expr) => write!(f, "throw {}", expr),
+            Stmt::Return(_, value) => match value {
+                Some(value) => write!(f, "return {}", value),
+                None => write!(f, "return"),
+            },
+            Stmt::Try {
+                body,
+                catch,
+                finally,
+            } => {
+                write!(f, "try")?;
+                body.iter().try_for_each(|stmt| write!(f, "\n{}", stmt))?;
+                if let Some((name, handler)) = catch {
+                    write!(f, "\ncatch {}", name.lexeme)?;
+                    handler
+                        .iter()
+                        .try_for_each(|stmt| write!(f, "\n{}", stmt))?;
+                }
+                if let Some(finally) = finally {
+                    write!(f, "\nfinally")?;
+                    finally
+                        .iter()
+                        .try_for_each(|stmt| write!(f, "\n{}", stmt))?;
+                }
+                Ok(())
+            }
+            Stmt::ForIn {
+                label,
+                variable,
+                iterable,
+                body,
+                ..
+            } => {
+                if let Some(label) = label {
+                    write!(f, "{}: ", label.lexeme)?;
+                }
+                write!(f, "for {} in {}", variable.lexeme, iterable)?;
+                body.iter().try_for_each(|stmt| write!(f, "\n{}", stmt))
+            }
+            Stmt::While {
+                label,
+                condition,
+                body,
+            } => {
+                if let Some(label) = label {
+                    write!(f, "{}: ", label.lexeme)?;
+                }
+                write!(f, "while {}", condition)?;
+                body.iter().try_for_each(|stmt| write!(f, "\n{}", stmt))
+            }
+            Stmt::Match { value, arms, .. } => {
+                write!(f, "match {}", value)?;
+                for arm in arms {
+                    write!(f, "\n{}", arm.pattern)?;
+                    if let Some(guard) = &arm.guard {
+                        write!(f, " if {}", guard)?;
+                    }
+                    write!(f, " =>")?;
+                    arm.body
+                        .iter()
+                        .try_for_each(|stmt| write!(f, " {}", stmt))?;
+                }
+                Ok(())
+            }
+            Stmt::Enum { name, variants } => {
+                write!(f, "enum {}", name.lexeme)?;
+                for variant in variants {
+                    write!(f, " {}", variant.name.lexeme)?;
+                    if !variant.fields.is_empty() {
+                        let fields = variant
+                            .fields
+                            .iter()
+                            .map(|field| field.lexeme.as_str())
+                            .collect::<Vec<&str>>()
+                            .join(", ");
+                        write!(f, "({fields})")?;
+                    }
+                }
+                Ok(())
+            }
+            Stmt::Yield(_, value) => match value {
+                Some(value) => write!(f, "yield {}", value),
+                None => write!(f, "yield"),
+            },
+            Stmt::Break(keyword, label) | Stmt::Continue(keyword, label) => match label {
+                Some(label) => write!(f, "{} {}", keyword.lexeme, label.lexeme),
+                None => write!(f, "{}", keyword.lexeme),
+            },
         }
     }
 }
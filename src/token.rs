@@ -1,3 +1,4 @@
+use crate::format::float_literal;
 use std::fmt;
 
 #[derive(Clone, Debug, PartialEq)]
@@ -6,25 +7,52 @@ pub enum TokenType {
     RIGHTPAREN,
     LEFTBRACE,
     RIGHTBRACE,
+    LEFTBRACKET,
+    RIGHTBRACKET,
 
     COMMA,
     DOT,
+    DOTDOT,
+    DOTDOTEQUAL,
+    DOTDOTDOT,
     MINUS,
     PLUS,
     SEMICOLON,
     STAR,
+    QUESTION,
+    COLON,
+    QUESTIONQUESTION,
+    QUESTIONDOT,
+
+    PLUSEQUAL,
+    MINUSEQUAL,
+    STAREQUAL,
+    SLASHEQUAL,
+    PLUSPLUS,
+    MINUSMINUS,
+    STARSTAR,
+    PERCENT,
 
     ASSIGN,
     BANG,
     EQUAL,
     NOTEQUAL,
+    ARROW,
 
     LESS,
     LESSEQUAL,
     GREATER,
     GREATEREQUAL,
 
+    AMPERSAND,
+    PIPE,
+    CARET,
+    TILDE,
+    LESSLESS,
+    GREATERGREATER,
+
     SLASH,
+    TILDESLASH,
 
     STRING,
     NUMBER,
@@ -32,21 +60,35 @@ pub enum TokenType {
     IDENTIFIER,
 
     AND,
+    BREAK,
+    CATCH,
     CLASS,
+    CONST,
+    CONTINUE,
     ELSE,
+    ENUM,
     FALSE,
+    FINALLY,
     FOR,
+    FROM,
     FUN,
     IF,
+    IMPORT,
+    IN,
+    IS,
+    MATCH,
     NIL,
     OR,
     PRINT,
     RETURN,
     SUPER,
     THIS,
+    THROW,
     TRUE,
+    TRY,
     VAR,
     WHILE,
+    YIELD,
 
     EOF,
 }
@@ -58,40 +100,79 @@ impl fmt::Display for TokenType {
             TokenType::RIGHTPAREN => "RIGHT_PAREN",
             TokenType::LEFTBRACE => "LEFT_BRACE",
             TokenType::RIGHTBRACE => "RIGHT_BRACE",
+            TokenType::LEFTBRACKET => "LEFT_BRACKET",
+            TokenType::RIGHTBRACKET => "RIGHT_BRACKET",
             TokenType::COMMA => "COMMA",
             TokenType::DOT => "DOT",
+            TokenType::DOTDOT => "DOT_DOT",
+            TokenType::DOTDOTEQUAL => "DOT_DOT_EQUAL",
+            TokenType::DOTDOTDOT => "DOT_DOT_DOT",
             TokenType::MINUS => "MINUS",
             TokenType::PLUS => "PLUS",
             TokenType::SEMICOLON => "SEMICOLON",
             TokenType::STAR => "STAR",
+            TokenType::QUESTION => "QUESTION",
+            TokenType::COLON => "COLON",
+            TokenType::QUESTIONQUESTION => "QUESTION_QUESTION",
+            TokenType::QUESTIONDOT => "QUESTION_DOT",
+            TokenType::PLUSEQUAL => "PLUS_EQUAL",
+            TokenType::MINUSEQUAL => "MINUS_EQUAL",
+            TokenType::STAREQUAL => "STAR_EQUAL",
+            TokenType::SLASHEQUAL => "SLASH_EQUAL",
+            TokenType::PLUSPLUS => "PLUS_PLUS",
+            TokenType::MINUSMINUS => "MINUS_MINUS",
+            TokenType::STARSTAR => "STAR_STAR",
+            TokenType::PERCENT => "PERCENT",
             TokenType::ASSIGN => "EQUAL", // difference for testing
             TokenType::BANG => "BANG",
             TokenType::EQUAL => "EQUAL_EQUAL",
             TokenType::NOTEQUAL => "BANG_EQUAL",
+            TokenType::ARROW => "ARROW",
             TokenType::LESS => "LESS",
             TokenType::LESSEQUAL => "LESS_EQUAL",
             TokenType::GREATER => "GREATER",
             TokenType::GREATEREQUAL => "GREATER_EQUAL",
+            TokenType::AMPERSAND => "AMPERSAND",
+            TokenType::PIPE => "PIPE",
+            TokenType::CARET => "CARET",
+            TokenType::TILDE => "TILDE",
+            TokenType::LESSLESS => "LESS_LESS",
+            TokenType::GREATERGREATER => "GREATER_GREATER",
             TokenType::SLASH => "SLASH",
+            TokenType::TILDESLASH => "TILDE_SLASH",
             TokenType::STRING => "STRING",
             TokenType::NUMBER => "NUMBER",
             TokenType::IDENTIFIER => "IDENTIFIER",
             TokenType::AND => "AND",
+            TokenType::BREAK => "BREAK",
+            TokenType::CATCH => "CATCH",
             TokenType::CLASS => "CLASS",
+            TokenType::CONST => "CONST",
+            TokenType::CONTINUE => "CONTINUE",
             TokenType::ELSE => "ELSE",
+            TokenType::ENUM => "ENUM",
             TokenType::FALSE => "FALSE",
+            TokenType::FINALLY => "FINALLY",
             TokenType::FOR => "FOR",
+            TokenType::FROM => "FROM",
             TokenType::FUN => "FUN",
             TokenType::IF => "IF",
+            TokenType::IMPORT => "IMPORT",
+            TokenType::IN => "IN",
+            TokenType::IS => "IS",
+            TokenType::MATCH => "MATCH",
             TokenType::NIL => "NIL",
             TokenType::OR => "OR",
             TokenType::PRINT => "PRINT",
             TokenType::RETURN => "RETURN",
             TokenType::SUPER => "SUPER",
             TokenType::THIS => "THIS",
+            TokenType::THROW => "THROW",
             TokenType::TRUE => "TRUE",
+            TokenType::TRY => "TRY",
             TokenType::VAR => "VAR",
             TokenType::WHILE => "WHILE",
+            TokenType::YIELD => "YIELD",
             TokenType::EOF => "EOF",
         };
 
@@ -101,6 +182,7 @@ impl fmt::Display for TokenType {
 
 #[derive(Clone, Debug)]
 pub enum TokenLiteral {
+    Int(i64),
     Number(f64),
     String(String),
 }
@@ -132,16 +214,8 @@ impl Token {
 impl fmt::Display for Token {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let literal = match &self.literal {
-            Some(TokenLiteral::Number(n)) => {
-                let n_str = n.to_string();
-                if n_str.ends_with(".0") {
-                    format!("{n}")
-                } else if !n_str.contains('.') {
-                    format!("{n}.0")
-                } else {
-                    format!("{n}")
-                }
-            }
+            Some(TokenLiteral::Int(n)) => format!("{n}.0"),
+            Some(TokenLiteral::Number(n)) => float_literal(*n),
             Some(TokenLiteral::String(s)) => s.to_string(),
             None => "null".to_string(),
         };
@@ -0,0 +1,1042 @@
+use crate::{Expr, MatchArm, Param, Pattern, Stmt, Token, TokenType, Type, TypeAnnotation};
+use std::{collections::HashMap, rc::Rc};
+
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("[line {line}] Error with `{token}`: {message}")]
+pub struct TypeError {
+    pub token: String,
+    pub message: String,
+    pub line: usize,
+}
+
+/// Checks a program's types without running it, reporting the operations that would
+/// fail at runtime because of the kinds of values involved.
+///
+/// Types come from annotations, literals and the operators applied to them. Anything
+/// the checker can't pin down is `Any`, which is never an error, so a program without
+/// annotations only gets its plainly wrong operations reported. A variable without an
+/// annotation takes its initializer's type until it's assigned something else, and is
+/// `Any` within a loop that assigns it.
+pub struct TypeChecker {
+    // the bindings of each enclosing scope, innermost last
+    scopes: Vec<Scope>,
+    // what each enclosing function declares it returns, innermost last
+    returns: Vec<Return>,
+    errors: Vec<TypeError>,
+}
+
+struct Scope {
+    bindings: HashMap<String, Binding>,
+    // how many functions enclose the scope
+    depth: usize,
+}
+
+#[derive(Clone)]
+struct Binding {
+    ty: Type,
+    // annotated, `const` or from a `fun` declaration: the type holds wherever the
+    // binding is seen
+    fixed: bool,
+    // annotated: assigned values must match
+    declared: bool,
+    signature: Option<Rc<Signature>>,
+}
+
+/// What a call to a function declared in the program takes and returns.
+struct Signature {
+    name: String,
+    // the type of each parameter, with whether it has a default
+    params: Vec<(Type, bool)>,
+    rest: bool,
+    returns: Type,
+}
+
+struct Return {
+    name: String,
+    ty: Type,
+    // a generator's `return` only ends it, so its value isn't checked
+    generator: bool,
+}
+
+impl Default for TypeChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TypeChecker {
+    pub fn new() -> Self {
+        TypeChecker {
+            scopes: vec![Scope {
+                bindings: HashMap::new(),
+                depth: 0,
+            }],
+            returns: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn check(mut self, statements: &[Stmt]) -> Vec<TypeError> {
+        for stmt in statements {
+            self.statement(stmt);
+        }
+
+        self.errors
+    }
+
+    fn statement(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Expression(expr) | Stmt::Print(expr) | Stmt::Throw(_, expr) => {
+                self.expression(expr);
+            }
+            Stmt::Yield(_, value) => {
+                if let Some(value) = value {
+                    self.expression(value);
+                }
+            }
+            Stmt::Var(pattern, annotation, initializer) => {
+                self.declaration(pattern, annotation, initializer, false)
+            }
+            Stmt::Const(pattern, annotation, initializer) => {
+                self.declaration(pattern, annotation, initializer, true)
+            }
+            Stmt::Block(statements) => self.block(statements, Vec::new()),
+            Stmt::Import { alias, .. } => {
+                self.define(&alias.lexeme, Binding::inferred(Type::Module))
+            }
+            Stmt::FromImport { names, .. } => {
+                for name in names {
+                    self.define(&name.lexeme, Binding::inferred(Type::Any));
+                }
+            }
+            Stmt::Return(keyword, value) => {
+                let ty = match value {
+                    Some(value) => self.expression(value),
+                    None => Type::Nil,
+                };
+
+                let expected = match self.returns.last() {
+                    Some(expected) if !expected.generator && !expected.ty.accepts(ty) => expected,
+                    _ => return,
+                };
+                let message = format!(
+                    "Expected '{}' to return {}, got {ty}.",
+                    expected.name,
+                    article(expected.ty)
+                );
+                self.error(keyword, message);
+            }
+            Stmt::Try {
+                body,
+                catch,
+                finally,
+            } => {
+                self.block(body, Vec::new());
+                if let Some((name, handler)) = catch {
+                    self.block(handler, vec![(name, Binding::inferred(Type::Error))]);
+                }
+                if let Some(finally) = finally {
+                    self.block(finally, Vec::new());
+                }
+            }
+            Stmt::ForIn {
+                keyword,
+                variable,
+                iterable,
+                body,
+                ..
+            } => {
+                let element = match self.expression(iterable) {
+                    Type::Range => Type::Int,
+                    Type::String => Type::String,
+                    Type::Any | Type::List | Type::Map | Type::Generator => Type::Any,
+                    ty => {
+                        let message = format!(
+                            "Can only iterate over strings, lists, maps, ranges and generators; got {ty}."
+                        );
+                        self.error(keyword, message);
+                        Type::Any
+                    }
+                };
+
+                self.widen_assigned(body);
+                self.block(body, vec![(variable, Binding::inferred(element))]);
+            }
+            Stmt::While {
+                condition, body, ..
+            } => {
+                self.widen_assigned(body);
+                self.expression(condition);
+                self.block(body, Vec::new());
+            }
+            Stmt::Match { value, arms, .. } => {
+                let ty = self.expression(value);
+                for arm in arms {
+                    self.match_arm(arm, ty);
+                }
+            }
+            Stmt::Enum { name, .. } => {
+                let binding = Binding {
+                    ty: Type::Enum,
+                    fixed: true,
+                    declared: false,
+                    signature: None,
+                };
+                self.define(&name.lexeme, binding);
+            }
+            Stmt::Break(..) | Stmt::Continue(..) => (),
+        }
+    }
+
+    fn declaration(
+        &mut self,
+        pattern: &Pattern,
+        annotation: &Option<TypeAnnotation>,
+        initializer: &Expr,
+        is_const: bool,
+    ) {
+        // a function can call itself, so its signature is known before its body is
+        // checked; one from a `fun` declaration is taken to keep its binding, so calls
+        // to it from other functions are checked too
+        let (signature, fixed) = match (pattern, initializer) {
+            (
+                Pattern::Binding(name),
+                Expr::Function {
+                    name: declared,
+                    params,
+                    return_type,
+                    generator,
+                    ..
+                },
+            ) => {
+                let signature = Signature::new(&name.lexeme, params, return_type, *generator);
+                let signature = Rc::new(signature);
+                let fixed = is_const || declared.is_some();
+                self.define(
+                    &name.lexeme,
+                    Binding {
+                        ty: Type::Function,
+                        fixed,
+                        declared: false,
+                        signature: Some(signature.clone()),
+                    },
+                );
+                (Some(signature), fixed)
+            }
+            _ => (None, is_const),
+        };
+
+        // a function bound to a name is reported under that name
+        let ty = match (pattern, initializer) {
+            (
+                Pattern::Binding(name),
+                Expr::Function {
+                    params,
+                    return_type,
+                    body,
+                    generator,
+                    ..
+                },
+            ) => {
+                self.function(&name.lexeme, params, return_type, body, *generator);
+                Type::Function
+            }
+            _ => self.expression(initializer),
+        };
+
+        let declared = annotation
+            .as_ref()
+            .map(|annotation| (annotation, self.annotated(annotation)));
+        if let Some((annotation, declared)) = declared {
+            if !declared.accepts(ty) {
+                let message = match pattern {
+                    Pattern::Binding(name) => format!(
+                        "Cannot assign {} to '{}', which is {}.",
+                        article(ty),
+                        name.lexeme,
+                        article(declared)
+                    ),
+                    _ => format!("Expected {}, got {ty}.", article(declared)),
+                };
+                self.error(&annotation.name, message);
+            }
+        }
+
+        match pattern {
+            Pattern::Binding(name) => {
+                let binding = Binding {
+                    ty: declared.map_or(ty, |(_, declared)| declared),
+                    fixed: fixed || annotation.is_some(),
+                    declared: annotation.is_some(),
+                    signature,
+                };
+                self.define(&name.lexeme, binding);
+            }
+            pattern => {
+                self.destructure(pattern, ty);
+                for name in pattern.bindings() {
+                    self.define(&name.lexeme, Binding::inferred(Type::Any));
+                }
+            }
+        }
+    }
+
+    // reports destructuring a value that's plainly the wrong shape
+    fn destructure(&mut self, pattern: &Pattern, ty: Type) {
+        let (token, expected) = match pattern {
+            Pattern::List { bracket, .. } => (bracket, Type::List),
+            Pattern::Map { brace, .. } => (brace, Type::Map),
+            _ => return,
+        };
+
+        if !expected.accepts(ty) {
+            self.error(
+                token,
+                format!("Expected a {expected} to destructure, got {ty}."),
+            );
+        }
+    }
+
+    fn match_arm(&mut self, arm: &MatchArm, ty: Type) {
+        // only a bare name is known to hold the whole value
+        let bindings = match &arm.pattern {
+            Pattern::Binding(name) => vec![(name, Binding::inferred(ty))],
+            pattern => pattern
+                .bindings()
+                .into_iter()
+                .map(|name| (name, Binding::inferred(Type::Any)))
+                .collect(),
+        };
+
+        self.begin_scope(bindings);
+        if let Some(guard) = &arm.guard {
+            self.expression(guard);
+        }
+        for stmt in arm.body.iter() {
+            self.statement(stmt);
+        }
+        self.scopes.pop();
+    }
+
+    fn block(&mut self, statements: &[Stmt], bindings: Vec<(&Token, Binding)>) {
+        self.begin_scope(bindings);
+        for stmt in statements {
+            self.statement(stmt);
+        }
+        self.scopes.pop();
+    }
+
+    fn begin_scope(&mut self, bindings: Vec<(&Token, Binding)>) {
+        self.scopes.push(Scope {
+            bindings: bindings
+                .into_iter()
+                .map(|(name, binding)| (name.lexeme.clone(), binding))
+                .collect(),
+            depth: self.returns.len(),
+        });
+    }
+
+    // the type an annotation declares. A name other than a built-in type must name an
+    // enum, or something the checker can't tell apart from one, like an imported name;
+    // an unknown one is reported and then taken as `Any`
+    fn annotated(&mut self, annotation: &TypeAnnotation) -> Type {
+        let name = &annotation.name;
+        if Type::from_name(&name.lexeme).is_some() {
+            return annotation.ty;
+        }
+
+        match self.lookup(&name.lexeme) {
+            Some(binding) if matches!(binding.ty, Type::Enum | Type::Any) => annotation.ty,
+            _ => {
+                self.error(name, format!("Unknown type '{}'.", name.lexeme));
+                Type::Any
+            }
+        }
+    }
+
+    fn define(&mut self, name: &str, binding: Binding) {
+        let scope = self.scopes.last_mut().expect("checker always has a scope");
+        scope.bindings.insert(name.to_string(), binding);
+    }
+
+    /// The binding `name` refers to. Inside a function, an outer variable that isn't
+    /// fixed may have been reassigned by the time the function runs, so it's `Any`.
+    fn lookup(&self, name: &str) -> Option<Binding> {
+        let scope = self
+            .scopes
+            .iter()
+            .rev()
+            .find(|scope| scope.bindings.contains_key(name))?;
+        let binding = &scope.bindings[name];
+
+        match scope.depth == self.returns.len() || binding.fixed {
+            true => Some(binding.clone()),
+            false => Some(Binding::inferred(Type::Any)),
+        }
+    }
+
+    // an assignment to `name`, which must suit an annotated variable and otherwise
+    // widens the variable to `Any` if it changes its type
+    fn assign(&mut self, name: &Token, ty: Type) {
+        let Some(binding) = self
+            .scopes
+            .iter_mut()
+            .rev()
+            .find_map(|scope| scope.bindings.get_mut(&name.lexeme))
+        else {
+            return;
+        };
+
+        if binding.declared {
+            if !binding.ty.accepts(ty) {
+                let message = format!(
+                    "Cannot assign {} to '{}', which is {}.",
+                    article(ty),
+                    name.lexeme,
+                    article(binding.ty)
+                );
+                self.error(name, message);
+            }
+        } else if binding.ty != ty {
+            *binding = Binding::inferred(Type::Any);
+        }
+    }
+
+    // a loop body sees the values its earlier iterations assigned, so the variables it
+    // assigns are `Any` before it's checked, unless they're annotated
+    fn widen_assigned(&mut self, body: &[Stmt]) {
+        let mut names = Vec::new();
+        assigned_in(body, &mut names);
+        for name in names {
+            self.assign(name, Type::Any);
+        }
+    }
+
+    fn expression(&mut self, expr: &Expr) -> Type {
+        match expr {
+            Expr::Int(_) => Type::Int,
+            Expr::Number(_) => Type::Number,
+            Expr::String(_) => Type::String,
+            Expr::Bool(_) => Type::Bool,
+            Expr::Nil => Type::Nil,
+            Expr::List(elements) => {
+                for element in elements {
+                    self.expression(element);
+                }
+                Type::List
+            }
+            Expr::Map { entries, .. } => {
+                for (key, value) in entries {
+                    self.expression(key);
+                    self.expression(value);
+                }
+                Type::Map
+            }
+            Expr::Grouping(expr) => self.expression(expr),
+            Expr::Unary { operator, right } => {
+                let right = self.expression(right);
+                self.unary(operator, right)
+            }
+            Expr::Binary {
+                operator,
+                left,
+                right,
+            } => {
+                let (left, right) = (self.expression(left), self.expression(right));
+                self.binary(operator, left, right)
+            }
+            Expr::Logical { left, right, .. } => {
+                // `??` gives the left operand unless it's nil
+                match (self.expression(left), self.expression(right)) {
+                    (Type::Nil, right) => right,
+                    (Type::Any, _) => Type::Any,
+                    (left, _) => left,
+                }
+            }
+            Expr::Is { value, .. } => {
+                self.expression(value);
+                Type::Bool
+            }
+            Expr::Range {
+                start,
+                operator,
+                end,
+            } => {
+                for bound in [start, end] {
+                    let ty = self.expression(bound);
+                    if !Type::Int.accepts(ty) {
+                        self.error(
+                            operator,
+                            format!("Range bounds must be integers; got {ty}."),
+                        );
+                    }
+                }
+                Type::Range
+            }
+            Expr::Conditional {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.expression(condition);
+                match (self.expression(then_branch), self.expression(else_branch)) {
+                    (then_type, else_type) if then_type == else_type => then_type,
+                    (then_type, else_type) if then_type.is_numeric() && else_type.is_numeric() => {
+                        Type::Number
+                    }
+                    _ => Type::Any,
+                }
+            }
+            Expr::Call {
+                callee,
+                paren,
+                arguments,
+                optional,
+            } => {
+                let signature = match callee.as_ref() {
+                    Expr::Variable(name) => self
+                        .lookup(&name.lexeme)
+                        .and_then(|binding| binding.signature),
+                    _ => None,
+                };
+                let callee = self.expression(callee);
+                let arguments = arguments
+                    .iter()
+                    .map(|argument| self.expression(argument))
+                    .collect::<Vec<Type>>();
+
+                match (callee, signature) {
+                    (Type::Any | Type::Function, None) => Type::Any,
+                    (Type::Nil, _) if *optional => Type::Any,
+                    (Type::Function, Some(signature)) => self.call(paren, &signature, &arguments),
+                    _ => {
+                        self.error(paren, "Can only call functions and classes.".to_string());
+                        Type::Any
+                    }
+                }
+            }
+            Expr::Get {
+                object,
+                name,
+                optional,
+            } => match (self.expression(object), name.lexeme.as_str()) {
+                (Type::String, "length") => Type::Int,
+                (Type::Error, "message") => Type::String,
+                (Type::Error, "line") => Type::Int,
+                (Type::Nil, _) if *optional => Type::Any,
+                (
+                    Type::Int
+                    | Type::Number
+                    | Type::Bool
+                    | Type::List
+                    | Type::Map
+                    | Type::Function
+                    | Type::Nil,
+                    _,
+                ) => {
+                    self.error(name, "Only instances have properties.".to_string());
+                    Type::Any
+                }
+                _ => Type::Any,
+            },
+            Expr::Index {
+                object,
+                bracket,
+                index,
+            } => {
+                let (object, index) = (self.expression(object), self.expression(index));
+                self.index(bracket, object, index)
+            }
+            Expr::Slice {
+                object,
+                bracket,
+                start,
+                end,
+            } => {
+                let object = self.expression(object);
+                for bound in [start, end].into_iter().flatten() {
+                    let ty = self.expression(bound);
+                    if !Type::Int.accepts(ty) && ty != Type::Nil {
+                        self.error(bracket, "Slice bounds must be integers.".to_string());
+                    }
+                }
+
+                match object {
+                    Type::String | Type::List | Type::Any => object,
+                    _ => {
+                        self.error(bracket, "Only strings and lists can be sliced.".to_string());
+                        Type::Any
+                    }
+                }
+            }
+            Expr::SetIndex {
+                object,
+                bracket,
+                index,
+                value,
+            } => {
+                let (object, index) = (self.expression(object), self.expression(index));
+                let value = self.expression(value);
+
+                if object == Type::String {
+                    self.error(bracket, "Strings are immutable.".to_string());
+                } else {
+                    self.index(bracket, object, index);
+                }
+                value
+            }
+            Expr::Variable(name) => self
+                .lookup(&name.lexeme)
+                .map_or(Type::Any, |binding| binding.ty),
+            Expr::Assign(name, value) => {
+                let ty = self.expression(value);
+                self.assign(name, ty);
+                ty
+            }
+            Expr::AssignPattern { pattern, value, .. } => {
+                let ty = self.expression(value);
+                self.destructure(pattern, ty);
+                for name in pattern.bindings() {
+                    self.assign(name, Type::Any);
+                }
+                ty
+            }
+            Expr::CompoundAssign {
+                target,
+                operator,
+                value,
+            } => {
+                let current = self.expression(target);
+                let value = self.expression(value);
+                let ty = self.binary(&arithmetic_operator(operator), current, value);
+
+                if let Expr::Variable(name) = target.as_ref() {
+                    self.assign(name, ty);
+                }
+                ty
+            }
+            Expr::Increment {
+                target, operator, ..
+            } => {
+                let ty = self.expression(target);
+                if !Type::Number.accepts(ty) {
+                    self.error(operator, "Operand must be a number.".to_string());
+                }
+                ty
+            }
+            Expr::Function {
+                name,
+                params,
+                return_type,
+                body,
+                generator,
+                ..
+            } => {
+                let name = name.as_ref().map_or("<fn>", |name| &name.lexeme);
+                self.function(name, params, return_type, body, *generator);
+                Type::Function
+            }
+        }
+    }
+
+    fn function(
+        &mut self,
+        name: &str,
+        params: &[Param],
+        return_type: &Option<TypeAnnotation>,
+        body: &[Stmt],
+        generator: bool,
+    ) {
+        // defaults are evaluated in the call's scope, after the earlier parameters
+        let returns = return_type
+            .as_ref()
+            .map_or(Type::Any, |annotation| self.annotated(annotation));
+        self.returns.push(Return {
+            name: name.to_string(),
+            ty: returns,
+            generator,
+        });
+        self.begin_scope(Vec::new());
+
+        for param in params {
+            let ty = match (&param.annotation, param.rest) {
+                (_, true) => Type::List,
+                (Some(annotation), false) => self.annotated(annotation),
+                (None, false) => Type::Any,
+            };
+
+            if let Some(default) = &param.default {
+                let default = self.expression(default);
+                if !ty.accepts(default) {
+                    let message = format!(
+                        "Default value for '{}' must be {}, got {default}.",
+                        param.name.lexeme,
+                        article(ty)
+                    );
+                    self.error(&param.name, message);
+                }
+            }
+
+            let binding = Binding {
+                ty,
+                fixed: param.annotation.is_some(),
+                declared: param.annotation.is_some(),
+                signature: None,
+            };
+            self.define(&param.name.lexeme, binding);
+        }
+
+        for stmt in body {
+            self.statement(stmt);
+        }
+
+        self.scopes.pop();
+        self.returns.pop();
+    }
+
+    fn call(&mut self, paren: &Token, signature: &Signature, arguments: &[Type]) -> Type {
+        let min = signature
+            .params
+            .iter()
+            .filter(|(_, default)| !default)
+            .count();
+        let max = signature.params.len();
+
+        if arguments.len() < min || (!signature.rest && arguments.len() > max) {
+            let expected = match (signature.rest, min == max) {
+                (true, _) => format!("at least {min}"),
+                (false, true) => min.to_string(),
+                (false, false) => format!("{min} to {max}"),
+            };
+            let message = format!("Expected {expected} arguments but got {}.", arguments.len());
+            self.error(paren, message);
+        }
+
+        for (i, (expected, argument)) in signature.params.iter().zip(arguments).enumerate() {
+            if !expected.0.accepts(*argument) {
+                let message = format!(
+                    "Argument {} to '{}' must be {}, got {argument}.",
+                    i + 1,
+                    signature.name,
+                    article(expected.0)
+                );
+                self.error(paren, message);
+            }
+        }
+
+        signature.returns
+    }
+
+    fn unary(&mut self, operator: &Token, right: Type) -> Type {
+        match operator.token_type {
+            TokenType::BANG => Type::Bool,
+            TokenType::MINUS => {
+                if !Type::Number.accepts(right) {
+                    self.error(operator, "Operand must be a number.".to_string());
+                    return Type::Any;
+                }
+                right
+            }
+            TokenType::TILDE => {
+                if !Type::Int.accepts(right) {
+                    self.error(operator, "Operand must be an integer.".to_string());
+                }
+                Type::Int
+            }
+            _ => Type::Any,
+        }
+    }
+
+    // the messages match the interpreter's for the same operands
+    fn binary(&mut self, operator: &Token, left: Type, right: Type) -> Type {
+        if left == Type::Any {
+            return match operator.token_type {
+                TokenType::EQUAL | TokenType::NOTEQUAL => Type::Bool,
+                _ => Type::Any,
+            };
+        }
+
+        let numbers = |message: &str| match (left, right) {
+            _ if !Type::Number.accepts(left) || !Type::Number.accepts(right) => {
+                Err(message.to_string())
+            }
+            (Type::Int, Type::Int) => Ok(Type::Int),
+            (_, Type::Any) => Ok(Type::Any),
+            _ => Ok(Type::Number),
+        };
+
+        let result = match operator.token_type {
+            TokenType::EQUAL | TokenType::NOTEQUAL => Ok(Type::Bool),
+            TokenType::PLUS => match (left, right) {
+                (Type::String, Type::String | Type::Any) => Ok(Type::String),
+                _ => numbers("Operands must be two numbers or two strings."),
+            },
+            TokenType::SLASH => numbers("Operands must be numbers").map(|_| Type::Number),
+            TokenType::MINUS | TokenType::STAR | TokenType::PERCENT | TokenType::TILDESLASH => {
+                numbers("Operands must be numbers")
+            }
+            // a negative integer exponent gives a float
+            TokenType::STARSTAR => numbers("Operands must be numbers").map(|ty| match ty {
+                Type::Int => Type::Any,
+                ty => ty,
+            }),
+            TokenType::GREATER
+            | TokenType::GREATEREQUAL
+            | TokenType::LESS
+            | TokenType::LESSEQUAL => numbers("Operands must be numbers").map(|_| Type::Bool),
+            TokenType::AMPERSAND
+            | TokenType::PIPE
+            | TokenType::CARET
+            | TokenType::LESSLESS
+            | TokenType::GREATERGREATER => {
+                match Type::Int.accepts(left) && Type::Int.accepts(right) {
+                    true => Ok(Type::Int),
+                    false => Err("Operands must be integers.".to_string()),
+                }
+            }
+            _ => Ok(Type::Any),
+        };
+
+        result.unwrap_or_else(|message| {
+            self.error(operator, message);
+            Type::Any
+        })
+    }
+
+    fn index(&mut self, bracket: &Token, object: Type, index: Type) -> Type {
+        match object {
+            Type::String | Type::List if !Type::Int.accepts(index) => {
+                self.error(bracket, format!("{object} index must be an integer."));
+                Type::Any
+            }
+            Type::String => Type::String,
+            Type::List | Type::Map | Type::Any => Type::Any,
+            _ => {
+                let message = "Only lists, maps and strings can be indexed.".to_string();
+                self.error(bracket, message);
+                Type::Any
+            }
+        }
+    }
+
+    fn error(&mut self, token: &Token, message: String) {
+        self.errors.push(TypeError {
+            token: token.lexeme.to_string(),
+            message,
+            line: token.line,
+        });
+    }
+}
+
+impl Binding {
+    fn inferred(ty: Type) -> Self {
+        Binding {
+            ty,
+            fixed: false,
+            declared: false,
+            signature: None,
+        }
+    }
+}
+
+impl Signature {
+    fn new(
+        name: &str,
+        params: &[Param],
+        return_type: &Option<TypeAnnotation>,
+        generator: bool,
+    ) -> Self {
+        let returns = match (generator, return_type) {
+            (true, _) => Type::Generator,
+            (false, Some(annotation)) => annotation.ty,
+            (false, None) => Type::Any,
+        };
+
+        Signature {
+            name: name.to_string(),
+            params: params
+                .iter()
+                .filter(|param| !param.rest)
+                .map(|param| {
+                    let ty = param.annotation.as_ref().map_or(Type::Any, |a| a.ty);
+                    (ty, param.default.is_some())
+                })
+                .collect(),
+            rest: params.iter().any(|param| param.rest),
+            returns,
+        }
+    }
+}
+
+// the variables `statements` assign to; the bodies of the functions they create only
+// run when called, so they aren't searched
+fn assigned_in<'a>(statements: &'a [Stmt], names: &mut Vec<&'a Token>) {
+    for stmt in statements {
+        match stmt {
+            Stmt::Expression(expr)
+            | Stmt::Print(expr)
+            | Stmt::Throw(_, expr)
+            | Stmt::Var(_, _, expr)
+            | Stmt::Const(_, _, expr)
+            | Stmt::Yield(_, Some(expr))
+            | Stmt::Return(_, Some(expr)) => assigned_in_expr(expr, names),
+            Stmt::Block(body) => assigned_in(body, names),
+            Stmt::Try {
+                body,
+                catch,
+                finally,
+            } => {
+                assigned_in(body, names);
+                if let Some((_, handler)) = catch {
+                    assigned_in(handler, names);
+                }
+                if let Some(finally) = finally {
+                    assigned_in(finally, names);
+                }
+            }
+            Stmt::ForIn { iterable, body, .. } => {
+                assigned_in_expr(iterable, names);
+                assigned_in(body, names);
+            }
+            Stmt::While {
+                condition, body, ..
+            } => {
+                assigned_in_expr(condition, names);
+                assigned_in(body, names);
+            }
+            Stmt::Match { value, arms, .. } => {
+                assigned_in_expr(value, names);
+                for arm in arms {
+                    if let Some(guard) = &arm.guard {
+                        assigned_in_expr(guard, names);
+                    }
+                    assigned_in(&arm.body, names);
+                }
+            }
+            Stmt::Yield(_, None)
+            | Stmt::Return(_, None)
+            | Stmt::Break(..)
+            | Stmt::Continue(..)
+            | Stmt::Import { .. }
+            | Stmt::FromImport { .. }
+            | Stmt::Enum { .. } => (),
+        }
+    }
+}
+
+fn assigned_in_expr<'a>(expr: &'a Expr, names: &mut Vec<&'a Token>) {
+    match expr {
+        Expr::Assign(name, value) => {
+            names.push(name);
+            assigned_in_expr(value, names);
+        }
+        Expr::AssignPattern { pattern, value, .. } => {
+            names.extend(pattern.bindings());
+            assigned_in_expr(value, names);
+        }
+        Expr::CompoundAssign { target, value, .. } => {
+            if let Expr::Variable(name) = target.as_ref() {
+                names.push(name);
+            }
+            assigned_in_expr(target, names);
+            assigned_in_expr(value, names);
+        }
+        Expr::List(elements) => {
+            for element in elements {
+                assigned_in_expr(element, names);
+            }
+        }
+        Expr::Map { entries, .. } => {
+            for (key, value) in entries {
+                assigned_in_expr(key, names);
+                assigned_in_expr(value, names);
+            }
+        }
+        Expr::Call {
+            callee, arguments, ..
+        } => {
+            assigned_in_expr(callee, names);
+            for argument in arguments {
+                assigned_in_expr(argument, names);
+            }
+        }
+        Expr::Grouping(expr)
+        | Expr::Unary { right: expr, .. }
+        | Expr::Is { value: expr, .. }
+        | Expr::Get { object: expr, .. }
+        | Expr::Increment { target: expr, .. } => assigned_in_expr(expr, names),
+        Expr::Binary { left, right, .. }
+        | Expr::Logical { left, right, .. }
+        | Expr::Range {
+            start: left,
+            end: right,
+            ..
+        }
+        | Expr::Index {
+            object: left,
+            index: right,
+            ..
+        } => {
+            assigned_in_expr(left, names);
+            assigned_in_expr(right, names);
+        }
+        Expr::Conditional {
+            condition,
+            then_branch,
+            else_branch,
+        } => {
+            for expr in [condition, then_branch, else_branch] {
+                assigned_in_expr(expr, names);
+            }
+        }
+        Expr::Slice {
+            object, start, end, ..
+        } => {
+            assigned_in_expr(object, names);
+            for bound in [start, end].into_iter().flatten() {
+                assigned_in_expr(bound, names);
+            }
+        }
+        Expr::SetIndex {
+            object,
+            index,
+            value,
+            ..
+        } => {
+            for expr in [object, index, value] {
+                assigned_in_expr(expr, names);
+            }
+        }
+        Expr::Int(_)
+        | Expr::Number(_)
+        | Expr::String(_)
+        | Expr::Bool(_)
+        | Expr::Nil
+        | Expr::Variable(_)
+        | Expr::Function { .. } => (),
+    }
+}
+
+// the operator behind `+=` and friends
+fn arithmetic_operator(operator: &Token) -> Token {
+    let token_type = match operator.token_type {
+        TokenType::PLUSEQUAL => TokenType::PLUS,
+        TokenType::MINUSEQUAL => TokenType::MINUS,
+        TokenType::STAREQUAL => TokenType::STAR,
+        TokenType::SLASHEQUAL => TokenType::SLASH,
+        _ => return operator.clone(),
+    };
+
+    Token {
+        token_type,
+        ..operator.clone()
+    }
+}
+
+// "an Int", "a String"
+fn article(ty: Type) -> String {
+    match ty.name().starts_with(['A', 'E', 'I', 'O', 'U']) {
+        true => format!("an {ty}"),
+        false => format!("a {ty}"),
+    }
+}
@@ -0,0 +1,104 @@
+use crate::Token;
+use std::fmt::{Display, Formatter};
+
+/// A static type: one of the runtime type names, or `Any` for a value the checker
+/// knows nothing about.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Type {
+    Any,
+    Bool,
+    Int,
+    /// Any number, so an `Int` is also a `Number`.
+    Number,
+    String,
+    List,
+    Map,
+    Range,
+    Function,
+    Generator,
+    Error,
+    Module,
+    Enum,
+    Variant,
+    Nil,
+}
+
+impl Type {
+    const ALL: [Type; 15] = [
+        Type::Any,
+        Type::Bool,
+        Type::Int,
+        Type::Number,
+        Type::String,
+        Type::List,
+        Type::Map,
+        Type::Range,
+        Type::Function,
+        Type::Generator,
+        Type::Error,
+        Type::Module,
+        Type::Enum,
+        Type::Variant,
+        Type::Nil,
+    ];
+
+    /// The type a name in an annotation or an `is` test stands for.
+    pub fn from_name(name: &str) -> Option<Type> {
+        Type::ALL.into_iter().find(|ty| ty.name() == name)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Type::Any => "Any",
+            Type::Bool => "Bool",
+            Type::Int => "Int",
+            Type::Number => "Number",
+            Type::String => "String",
+            Type::List => "List",
+            Type::Map => "Map",
+            Type::Range => "Range",
+            Type::Function => "Function",
+            Type::Generator => "Generator",
+            Type::Error => "Error",
+            Type::Module => "Module",
+            Type::Enum => "Enum",
+            Type::Variant => "Variant",
+            Type::Nil => "Nil",
+        }
+    }
+
+    /// Whether a value of type `other` can be used where `self` is expected. `Any`
+    /// goes both ways, since the checker can't rule it out.
+    pub fn accepts(&self, other: Type) -> bool {
+        match (self, other) {
+            (Type::Any, _) | (_, Type::Any) => true,
+            (Type::Number, Type::Int) => true,
+            (expected, other) => *expected == other,
+        }
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        matches!(self, Type::Int | Type::Number)
+    }
+}
+
+impl Display for Type {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// A `: Type` annotation, keeping the type name's token for error reporting. A name
+/// that isn't one of the built-in types names an enum, so its values are variants.
+/// The interpreter ignores annotations; only `typecheck` reads them.
+#[derive(Debug, Clone)]
+pub struct TypeAnnotation {
+    pub name: Token,
+    pub ty: Type,
+}
+
+impl Display for TypeAnnotation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name.lexeme)
+    }
+}
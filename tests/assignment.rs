@@ -0,0 +1,27 @@
+mod common;
+
+use common::run;
+
+#[test]
+fn compound_assignment_reads_the_target_before_the_right_hand_side() {
+    let source = "
+        var x = 0;
+        x += (x = 5);
+        print x;
+    ";
+
+    assert_eq!(run(source), "5\n");
+}
+
+#[test]
+fn compound_assignment_evaluates_the_index_first() {
+    let source = r#"
+        var xs = [10, 20];
+        fun i() { print "index"; return 1; }
+        fun f() { print "value"; return 2; }
+        xs[i()] += f();
+        print xs;
+    "#;
+
+    assert_eq!(run(source), "index\nvalue\n[10, 22]\n");
+}
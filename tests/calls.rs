@@ -0,0 +1,39 @@
+mod common;
+
+use common::{interpret, run};
+
+#[test]
+fn deep_recursion_within_the_limit_runs() {
+    let source = "
+        fun depth(n) { return n == 0 ? 0 : 1 + depth(n - 1); }
+        print depth(900);
+    ";
+
+    assert_eq!(run(source), "900\n");
+}
+
+#[test]
+fn runaway_recursion_is_a_runtime_error() {
+    let source = "
+        fun forever(n) { return forever(n + 1); }
+        forever(0);
+    ";
+
+    let output = interpret("run", source);
+    assert_eq!(output.code, 70);
+    assert_eq!(
+        output.stderr.trim(),
+        "[line 2] Error with `)`: Stack overflow."
+    );
+}
+
+#[test]
+fn stack_overflow_can_be_caught() {
+    let source = r#"
+        fun forever() { return forever(); }
+        try { forever(); } catch (e) { print e.message; }
+        print "still running";
+    "#;
+
+    assert_eq!(run(source), "Stack overflow.\nstill running\n");
+}
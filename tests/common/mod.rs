@@ -0,0 +1,47 @@
+// each test crate uses its own share of these helpers
+#![allow(dead_code)]
+
+use std::{
+    env, fs,
+    process::Command,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// What running the interpreter on a program printed, and the code it exited with.
+pub struct Output {
+    pub stdout: String,
+    pub stderr: String,
+    pub code: i32,
+}
+
+/// Runs `command` (`run`, `typecheck`, ...) on `source`, written out to a script file.
+pub fn interpret(command: &str, source: &str) -> Output {
+    static SCRIPTS: AtomicUsize = AtomicUsize::new(0);
+
+    let script = env::temp_dir().join(format!(
+        "lox-test-{}-{}.lox",
+        std::process::id(),
+        SCRIPTS.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::write(&script, source).expect("should be able to write the script");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_interpreter-starter-rust"))
+        .arg(command)
+        .arg(&script)
+        .output()
+        .expect("should be able to run the interpreter");
+    fs::remove_file(&script).ok();
+
+    Output {
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        code: output.status.code().unwrap_or(-1),
+    }
+}
+
+/// Runs `source`, expecting it to finish cleanly, and gives what it printed.
+pub fn run(source: &str) -> String {
+    let output = interpret("run", source);
+    assert_eq!(output.code, 0, "unexpected failure: {}", output.stderr);
+    output.stdout
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use common::{interpret, run};
+
+#[test]
+fn a_failed_pattern_assignment_assigns_nothing() {
+    let source = "
+        var a = 0;
+        try {
+            [a, zz] = [5, 6];
+        } catch (e) {
+            print e.message;
+        }
+        print a;
+
+        try {
+            [a, [zz]] = [5, 6];
+        } catch (e) {
+            print e.message;
+        }
+        print a;
+    ";
+
+    assert_eq!(
+        run(source),
+        "Undefined variable 'zz'\n0\nExpected a List to destructure, got Int.\n0\n"
+    );
+}
+
+#[test]
+fn map_patterns_assign_at_the_start_of_a_statement() {
+    let source = r#"
+        var p = {"x": 1, "y": 2};
+        var a = 0;
+        var x = 0;
+        var y = 0;
+        {"x": a} = p;
+        print a;
+        {x, y} = p;
+        print x + y;
+        { print "still a block"; }
+    "#;
+
+    assert_eq!(run(source), "1\n3\nstill a block\n");
+}
+
+#[test]
+fn list_patterns_assign_a_rest() {
+    let source = "
+        var first = nil;
+        var rest = nil;
+        [first, ...rest] = [1, 2, 3];
+        print first;
+        print rest;
+    ";
+
+    assert_eq!(run(source), "1\n[2, 3]\n");
+}
+
+#[test]
+fn pattern_errors_point_at_the_pattern_that_failed() {
+    let source = "
+        var a = 0;
+        var b = 0;
+        [a,
+            [b]] = [1, 2];
+    ";
+
+    let output = interpret("run", source);
+    assert_eq!(output.code, 70);
+    assert_eq!(
+        output.stderr.trim(),
+        "[line 5] Error with `[`: Expected a List to destructure, got Int."
+    );
+}
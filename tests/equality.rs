@@ -0,0 +1,27 @@
+mod common;
+
+use common::run;
+
+#[test]
+fn generators_are_only_equal_to_themselves() {
+    let source = "
+        fun gen() { yield 1; }
+        var g = gen();
+        var h = gen();
+        print g == g;
+        print g == h;
+        print g != g;
+    ";
+
+    assert_eq!(run(source), "true\nfalse\nfalse\n");
+}
+
+#[test]
+fn native_functions_are_only_equal_to_themselves() {
+    let source = "
+        print type == type;
+        print type == fields;
+    ";
+
+    assert_eq!(run(source), "true\nfalse\n");
+}
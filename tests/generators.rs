@@ -0,0 +1,123 @@
+mod common;
+
+use common::{interpret, run};
+
+#[test]
+fn yield_inside_try_runs_finally_once_resumed_past_it() {
+    let source = r#"
+        fun guarded() {
+            try {
+                yield 1;
+                yield 2;
+            } finally {
+                print "cleanup";
+            }
+            yield 3;
+        }
+        for (x in guarded()) print x;
+    "#;
+
+    assert_eq!(run(source), "1\n2\ncleanup\n3\n");
+}
+
+#[test]
+fn yield_inside_catch_resumes_after_the_handler() {
+    let source = r#"
+        fun recovering() {
+            try {
+                yield "before";
+                throw "oops";
+            } catch (e) {
+                yield "caught " + e;
+            }
+            yield "after";
+        }
+        for (x in recovering()) print x;
+    "#;
+
+    assert_eq!(run(source), "before\ncaught oops\nafter\n");
+}
+
+#[test]
+fn labeled_continue_skips_to_the_outer_loop() {
+    let source = "
+        fun pairs() {
+            outer: for (i in 0..3) {
+                for (j in 0..3) {
+                    match (j > i) {
+                        true => continue outer;
+                        _ => yield [i, j];
+                    }
+                }
+            }
+        }
+        for (p in pairs()) print p;
+    ";
+
+    assert_eq!(
+        run(source),
+        "[0, 0]\n[1, 0]\n[1, 1]\n[2, 0]\n[2, 1]\n[2, 2]\n"
+    );
+}
+
+#[test]
+fn while_loops_continue_and_break_inside_generators() {
+    let source = "
+        fun counting() {
+            var n = 0;
+            while (true) {
+                n = n + 1;
+                match (n) {
+                    2 => continue;
+                    5 => break;
+                    _ => yield n;
+                }
+            }
+        }
+        for (x in counting()) print x;
+    ";
+
+    assert_eq!(run(source), "1\n3\n4\n");
+}
+
+#[test]
+fn next_and_for_in_raise_the_same_thrown_value() {
+    let source = r#"
+        fun failing() {
+            yield 1;
+            throw {"code": 7};
+        }
+
+        var g = failing();
+        g.next();
+        try { g.next(); } catch (e) { print e["code"]; }
+
+        try {
+            for (x in failing()) print x;
+        } catch (e) {
+            print e["code"];
+        }
+    "#;
+
+    assert_eq!(run(source), "7\n1\n7\n");
+}
+
+#[test]
+fn next_reports_errors_where_the_generator_raised_them() {
+    let source = "
+        fun failing() {
+            yield 1;
+            var y = nil + 1;
+        }
+        var g = failing();
+        g.next();
+        g.next();
+    ";
+
+    let output = interpret("run", source);
+    assert_eq!(output.code, 70);
+    assert_eq!(
+        output.stderr.trim(),
+        "[line 4] Error with `+`: Operands must be two numbers or two strings."
+    );
+}
@@ -0,0 +1,37 @@
+mod common;
+
+use common::interpret;
+
+fn runtime_error(source: &str) -> String {
+    let output = interpret("run", source);
+    assert_eq!(
+        output.code, 70,
+        "expected a runtime error: {}",
+        output.stdout
+    );
+    output.stderr.trim().to_string()
+}
+
+#[test]
+fn string_index_errors_name_strings() {
+    assert_eq!(
+        runtime_error(r#"print "abc"[5];"#),
+        "[line 1] Error with `[`: Index out of range: 5 for string of length 3."
+    );
+    assert_eq!(
+        runtime_error(r#"var i = "x"; print "abc"[i];"#),
+        "[line 1] Error with `[`: String index must be an integer."
+    );
+}
+
+#[test]
+fn list_index_errors_name_lists() {
+    assert_eq!(
+        runtime_error("print [1][3];"),
+        "[line 1] Error with `[`: Index out of range: 3 for list of length 1."
+    );
+    assert_eq!(
+        runtime_error(r#"var i = "x"; print [1][i];"#),
+        "[line 1] Error with `[`: List index must be an integer."
+    );
+}
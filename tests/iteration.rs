@@ -0,0 +1,51 @@
+mod common;
+
+use common::{interpret, run};
+
+#[test]
+fn maps_iterate_over_their_keys() {
+    let source = r#"
+        for (key in {"next": fun() { return nil; }, "a": 1}) print key;
+        for (key in {"iterator": fun() { return nil; }}) print key;
+    "#;
+
+    assert_eq!(run(source), "next\na\niterator\n");
+}
+
+#[test]
+fn iterable_maps_go_through_next() {
+    let source = r#"
+        var n = 0;
+        var counter = iterable({"next": fun() {
+            n = n + 1;
+            return n > 3 ? nil : n;
+        }});
+        for (x in counter) print x;
+    "#;
+
+    assert_eq!(run(source), "1\n2\n3\n");
+}
+
+#[test]
+fn iterable_maps_go_through_iterator() {
+    let source = r#"
+        fun pair() {
+            yield "left";
+            yield "right";
+        }
+        for (x in iterable({"iterator": pair})) print x;
+    "#;
+
+    assert_eq!(run(source), "left\nright\n");
+}
+
+#[test]
+fn iterable_maps_need_a_protocol_function() {
+    let output = interpret("run", r#"for (x in iterable({"a": 1})) print x;"#);
+
+    assert_eq!(output.code, 70);
+    assert_eq!(
+        output.stderr.trim(),
+        "[line 1] Error with `for`: An iterable map must have an 'iterator' or 'next' function."
+    );
+}
@@ -0,0 +1,25 @@
+mod common;
+
+use common::{interpret, run};
+
+#[test]
+fn alternatives_must_bind_the_same_names() {
+    let output = interpret("run", "match ([5]) { [x] | y => print y; }");
+
+    assert_eq!(output.code, 65);
+    assert_eq!(
+        output.stderr.trim(),
+        "All alternatives must bind the same variables [line 1]"
+    );
+}
+
+#[test]
+fn alternatives_binding_the_same_names_match() {
+    let source = r#"
+        match ([5]) { [x] | [x, _] => print x; }
+        match ({"a": 1}) { {a} | [a] => print a; }
+        match (3) { 1 | 2 => print "low"; _ => print "other"; }
+    "#;
+
+    assert_eq!(run(source), "5\n1\nother\n");
+}
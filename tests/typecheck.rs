@@ -0,0 +1,42 @@
+mod common;
+
+use common::interpret;
+
+#[test]
+fn loop_bodies_see_the_types_earlier_iterations_assign() {
+    let source = "
+        var best = nil;
+        for (v in [3, 1, 2]) {
+            best = best == nil ? v : (best < v ? v : best);
+        }
+        print best;
+
+        var total = 0;
+        var i = 0;
+        while (i < 3) {
+            total = total + 0.5;
+            i = i + 1;
+        }
+        print total;
+    ";
+
+    let output = interpret("typecheck", source);
+    assert_eq!(output.code, 0, "{}", output.stderr);
+}
+
+#[test]
+fn annotated_variables_are_still_checked_in_loops() {
+    let source = "
+        var count: Int = 0;
+        for (v in 0..3) {
+            count = count + \"a\";
+        }
+    ";
+
+    let output = interpret("typecheck", source);
+    assert_eq!(output.code, 65);
+    assert_eq!(
+        output.stderr.trim(),
+        "[line 4] Error with `+`: Operands must be two numbers or two strings."
+    );
+}